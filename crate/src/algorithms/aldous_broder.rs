@@ -1,8 +1,7 @@
 use crate::cells::ICellStrong;
-use crate::algorithms::MazeAlgorithm;
+use crate::algorithms::{MazeAlgorithm, link_cells, steps::{GenerationEvent, GenerationObserver, position}};
 use crate::rng::RngWrapper;
 use crate::grid::{Grid};
-use std::rc::Rc;
 
 #[derive(Debug)]
 pub struct AldousBroder;
@@ -14,9 +13,10 @@ pub struct AldousBroder;
 /// #3b if that neighbor HAS been visited, set the neighbor as the current cell, but do not link.
 /// Repeat until every cell has been visited.
 impl MazeAlgorithm for AldousBroder {
-    fn on(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) {
+    fn on_observed(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) {
 
         let mut cell = grid.random_cell(rng_generator).unwrap();
+        observer.notify(GenerationEvent::Visit(position(&cell)));
        
         // this only works becuase the maze is "perfect"
         let mut unvisited_cells = grid.size() - 1;
@@ -27,12 +27,12 @@ impl MazeAlgorithm for AldousBroder {
             let next_neighbor = &neighbors[index];
 
             if next_neighbor.borrow().links().is_empty() {
-                cell.borrow_mut().link(Rc::clone(next_neighbor));
-                next_neighbor.borrow_mut().link(Rc::clone(&cell));
+                link_cells(&cell, next_neighbor, observer);
                 unvisited_cells -= 1;
             }
 
             cell = next_neighbor.clone();
+            observer.notify(GenerationEvent::Visit(position(&cell)));
         }
     }
 }
\ No newline at end of file
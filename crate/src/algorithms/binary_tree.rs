@@ -1,36 +1,74 @@
 use crate::rng::RngWrapper;
 use crate::grid::{Grid};
 use crate::cells::*;
-use std::rc::{Rc};
-use crate::algorithms::{MazeAlgorithm, rand_element};
+use crate::algorithms::{MazeAlgorithm, Corner, bias_neighbors, rand_element, kruskal::KruskalState, steps::{GenerationEvent, GenerationObserver, position}};
 
-#[derive(Debug)]
-pub struct BinaryTree;
+#[derive(Debug, Default)]
+pub struct BinaryTree {
+    corner: Corner
+}
 
+impl BinaryTree {
+    pub fn new(corner: Corner) -> BinaryTree {
+        BinaryTree { corner }
+    }
+}
+
+/// Binary Tree Algorithm
+/// For each cell, link either its vertical or its horizontal neighbor towards the bias corner (see `bias_neighbors`).
+/// A cell on the far edge with neither, like every other cell at the end of a triangle row, steps back along
+/// the row instead, and the cell it steps back to then goes vertically so the two don't just link each other.
+/// Pieces that still can't reach the corner, like cells boxed in by a mask, are joined on afterwards
+/// through random neighbors so the result is a perfect maze.
 impl MazeAlgorithm for BinaryTree {
-    fn on(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) {
+    fn on_observed(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) {
+        let mut state = KruskalState::new(grid);
+
         for cell in grid.each_cell().iter() {
             if let Some(cell) = cell {
-                let mut neighbors: Vec<ICellStrong> = vec![];
-                if cell.borrow().row() > 0 {                    
-                    if let Some(north) = grid.get_cell(cell.borrow().row() - 1, cell.borrow().column()) {
-                        neighbors.push(Rc::clone(&north));
-                    };
-                }
+                observer.notify(GenerationEvent::Visit(position(cell)));
+                let neighbors = self.candidates(cell);
 
-                if cell.borrow().column() < grid.columns() - 1 {
-                    if let Some(east) = grid.get_cell(cell.borrow().row(), cell.borrow().column() + 1) {
-                        neighbors.push(Rc::clone(&east));
-                    };
-                }
-
-                let length =  neighbors.len();
-                if length > 0 {
-                    let neighbor: ICellStrong = rand_element(&neighbors, rng_generator).clone();
-                    cell.borrow_mut().link(Rc::clone(&neighbor));
-                    neighbor.borrow_mut().link(Rc::clone(cell));
+                if !neighbors.is_empty() {
+                    let neighbor = rand_element(&neighbors, rng_generator);
+                    if state.can_merge(cell, neighbor) {
+                        state.merge(cell, neighbor, observer);
+                    }
                 }
             }
         }
+
+        state.merge_remaining(rng_generator, observer);
+    }
+}
+
+impl BinaryTree {
+    fn candidates(&self, cell: &ICellStrong) -> Vec<ICellStrong> {
+        let (vertical, horizontal) = bias_neighbors(cell, self.corner);
+        if vertical.is_none() && horizontal.is_none() {
+            return self.step_back(cell).into_iter().collect();
+        }
+
+        // A stuck neighbor steps back to this cell itself.
+        let horizontal = horizontal.filter(|h| !self.is_stuck(h));
+        vec![vertical, horizontal].into_iter()
+            .filter_map(|n| n)
+            .collect()
+    }
+
+    fn is_stuck(&self, cell: &ICellStrong) -> bool {
+        let (vertical, horizontal) = bias_neighbors(cell, self.corner);
+        vertical.is_none() && horizontal.is_none()
+    }
+
+    /// The horizontal neighbor away from the bias corner.
+    fn step_back(&self, cell: &ICellStrong) -> Option<ICellStrong> {
+        let opposite = match self.corner {
+            Corner::NorthEast => Corner::NorthWest,
+            Corner::NorthWest => Corner::NorthEast,
+            Corner::SouthEast => Corner::SouthWest,
+            Corner::SouthWest => Corner::SouthEast,
+        };
+        bias_neighbors(cell, opposite).1
     }
-}
\ No newline at end of file
+}
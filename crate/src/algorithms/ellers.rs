@@ -0,0 +1,175 @@
+use crate::cells::ICellStrong;
+use crate::grid::{Grid};
+use crate::rng::RngWrapper;
+use crate::algorithms::{MazeAlgorithm, shuffle, kruskal::KruskalState, steps::{GenerationEvent, GenerationObserver, position}};
+
+#[derive(Debug)]
+pub struct Ellers;
+
+/// Eller's Algorithm
+/// Works one row at a time, tracking which set each cell of the current row belongs to.
+/// 1) Randomly link neighbors in the row that belong to different sets, merging the sets
+/// 2) Link at least one cell of every set to the row below; unlinked cells below start new sets
+/// 3) On the last row, link every pair of neighbors that are still in different sets
+/// Only meant for grids where every row has the same number of cells, like the standard grid.
+/// Passages into voids left by a mask or `sparsify` are skipped, and any pieces that cuts off
+/// are joined to the rest through random neighbors so the result is still a perfect maze.
+impl MazeAlgorithm for Ellers {
+    fn on_observed(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) {
+        let cells = grid.cells();
+        let columns = cells.get(0).map_or(0, |row| row.len());
+        let rows = EllerRows::new(columns, Some(cells.len()), rng_generator);
+        let mut state = KruskalState::new(grid);
+
+        for row in rows {
+            let current = &cells[row.row];
+            for cell in current.iter().filter_map(|c| c.as_ref()) {
+                observer.notify(GenerationEvent::Visit(position(cell)));
+            }
+
+            for (column, _) in row.east.iter().enumerate().filter(|(_, linked)| **linked) {
+                if let (Some(cell), Some(east)) = (&current[column], &current[column + 1]) {
+                    state.merge(cell, east, observer);
+                }
+            }
+
+            for (column, _) in row.south.iter().enumerate().filter(|(_, linked)| **linked) {
+                if let (Some(cell), Some(south)) = (&current[column], &cells[row.row + 1][column]) {
+                    state.merge(cell, south, observer);
+                }
+            }
+        }
+
+        state.merge_remaining(rng_generator, observer);
+    }
+}
+
+/// One finished row of an Eller's maze.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EllerRow {
+    pub row: usize,
+    /// `east[i]` is true when column `i` is linked to column `i + 1`. Has `columns - 1` entries.
+    pub east: Vec<bool>,
+    /// `south[i]` is true when column `i` is linked to the row below. All false on the last row.
+    pub south: Vec<bool>,
+}
+
+impl EllerRow {
+    /// Renders the row in the same `+---+` format as `GridBase::to_string`.
+    /// The first row also includes the top border.
+    pub fn to_string(&self) -> String {
+        let columns = self.south.len();
+        let mut output = String::new();
+
+        if self.row == 0 {
+            output += "+";
+            output += &"---+".repeat(columns);
+            output += "\r\n";
+        }
+
+        let mut top = String::from("|");
+        let mut bottom = String::from("+");
+        for column in 0..columns {
+            top += "   ";
+            top += if column < columns - 1 && self.east[column] { " " } else { "|" };
+            bottom += if self.south[column] { "   " } else { "---" };
+            bottom += "+";
+        }
+
+        output += &format!("{}\r\n", top);
+        output += &format!("{}\r\n", bottom);
+        output
+    }
+}
+
+/// Streams an Eller's maze one row at a time, only holding the set ids of the current row.
+/// With no height the stream never ends, so use `take` (and accept an open bottom) or give it a height.
+pub struct EllerRows<'a> {
+    columns: usize,
+    height: Option<usize>,
+    row: usize,
+    sets: Vec<usize>,
+    next_set: usize,
+    rng_generator: &'a dyn RngWrapper<Shuffle=ICellStrong>,
+}
+
+impl<'a> EllerRows<'a> {
+    pub fn new(columns: usize, height: Option<usize>, rng_generator: &'a dyn RngWrapper<Shuffle=ICellStrong>) -> EllerRows<'a> {
+        EllerRows {
+            columns,
+            height,
+            row: 0,
+            sets: (0..columns).collect(),
+            next_set: columns,
+            rng_generator,
+        }
+    }
+
+    fn merge(&mut self, winner: usize, loser: usize) {
+        for set in self.sets.iter_mut() {
+            if *set == loser {
+                *set = winner;
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for EllerRows<'a> {
+    type Item = EllerRow;
+
+    fn next(&mut self) -> Option<EllerRow> {
+        if let Some(height) = self.height {
+            if self.row >= height {
+                return None;
+            }
+        }
+
+        let last_row = self.height.map_or(false, |h| self.row == h - 1);
+        let mut east = vec![false; self.columns.saturating_sub(1)];
+        let mut south = vec![false; self.columns];
+
+        for column in 1..self.columns {
+            let (west_set, set) = (self.sets[column - 1], self.sets[column]);
+            let should_link = west_set != set && (last_row || self.rng_generator.gen_range(0, 2) == 0);
+
+            if should_link {
+                east[column - 1] = true;
+                self.merge(west_set, set);
+            }
+        }
+
+        if !last_row {
+            let mut next_sets: Vec<Option<usize>> = vec![None; self.columns];
+            let mut seen: Vec<usize> = vec![];
+
+            for &set in self.sets.iter() {
+                if seen.contains(&set) {
+                    continue;
+                }
+                seen.push(set);
+
+                let mut members: Vec<usize> = (0..self.columns).filter(|&c| self.sets[c] == set).collect();
+                shuffle(&mut members, self.rng_generator);
+                let count = 1 + self.rng_generator.gen_range(0, members.len());
+
+                for &column in members.iter().take(count) {
+                    south[column] = true;
+                    next_sets[column] = Some(set);
+                }
+            }
+
+            let mut next_set = self.next_set;
+            self.sets = next_sets.iter()
+                .map(|set| set.unwrap_or_else(|| {
+                    next_set += 1;
+                    next_set - 1
+                }))
+                .collect();
+            self.next_set = next_set;
+        }
+
+        let row = EllerRow { row: self.row, east, south };
+        self.row += 1;
+        Some(row)
+    }
+}
@@ -0,0 +1,117 @@
+use crate::cells::ICellStrong;
+use crate::grid::{Grid};
+use crate::rng::RngWrapper;
+use crate::algorithms::{MazeAlgorithm, rand_element, link_cells, steps::{GenerationEvent, GenerationObserver, position}};
+use std::fmt::Debug;
+use std::rc::{Rc};
+
+/// Decides which active cell the growing tree works from next.
+/// `active` is ordered oldest to newest; return an index into it.
+pub trait CellSelector: Debug {
+    fn select(&self, active: &[ICellStrong], rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) -> usize;
+}
+
+/// Always the most recently added cell. Behaves like the recursive backtracker.
+#[derive(Debug)]
+pub struct Newest;
+
+impl CellSelector for Newest {
+    fn select(&self, active: &[ICellStrong], _rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) -> usize {
+        active.len() - 1
+    }
+}
+
+/// Always the first cell added. Produces long, straight corridors.
+#[derive(Debug)]
+pub struct Oldest;
+
+impl CellSelector for Oldest {
+    fn select(&self, _active: &[ICellStrong], _rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) -> usize {
+        0
+    }
+}
+
+/// Any active cell. Behaves like simplified Prim's.
+#[derive(Debug)]
+pub struct Random;
+
+impl CellSelector for Random {
+    fn select(&self, active: &[ICellStrong], rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) -> usize {
+        rng_generator.gen_range(0, active.len())
+    }
+}
+
+/// Picks one of several selectors in proportion to its weight, e.g. 75 newest / 25 random.
+#[derive(Debug)]
+pub struct Weighted {
+    choices: Vec<(usize, Box<dyn CellSelector>)>,
+    total: usize
+}
+
+impl Weighted {
+    pub fn new(choices: Vec<(usize, Box<dyn CellSelector>)>) -> Weighted {
+        let total = choices.iter().fold(0, |acc, (weight, _)| acc + weight);
+        if total == 0 {
+            panic!("Weighted selector needs at least one choice with a non-zero weight.");
+        }
+
+        Weighted { choices, total }
+    }
+}
+
+impl CellSelector for Weighted {
+    fn select(&self, active: &[ICellStrong], rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) -> usize {
+        let mut roll = rng_generator.gen_range(0, self.total);
+        for (weight, selector) in self.choices.iter() {
+            if roll < *weight {
+                return selector.select(active, rng_generator);
+            }
+            roll -= weight;
+        }
+
+        unreachable!()
+    }
+}
+
+#[derive(Debug)]
+pub struct GrowingTree {
+    selector: Box<dyn CellSelector>
+}
+
+impl GrowingTree {
+    pub fn new(selector: Box<dyn CellSelector>) -> GrowingTree {
+        GrowingTree { selector }
+    }
+}
+
+/// Growing Tree Algorithm
+/// 1) Start with a random cell in the active list
+/// 2) Use the selector to choose an active cell
+/// 3) Link it to a random unvisited neighbor and add the neighbor to the list,
+///    or remove it from the list if it has no unvisited neighbors
+/// Repeat until the active list is empty.
+impl MazeAlgorithm for GrowingTree {
+    fn on_observed(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) {
+        let mut active: Vec<ICellStrong> = vec![grid.random_cell(rng_generator).unwrap()];
+
+        while !active.is_empty() {
+            let index = self.selector.select(&active, rng_generator);
+            let cell = Rc::clone(&active[index]);
+            observer.notify(GenerationEvent::Visit(position(&cell)));
+            let available: Vec<ICellStrong> = cell.borrow().neighbors()
+                .iter()
+                .filter(|n| n.borrow().links().is_empty())
+                .map(|n| Rc::clone(n))
+                .collect();
+
+            if available.is_empty() {
+                active.remove(index);
+                observer.notify(GenerationEvent::Backtrack(position(&cell)));
+            } else {
+                let neighbor = rand_element(&available, rng_generator);
+                link_cells(&cell, neighbor, observer);
+                active.push(Rc::clone(neighbor));
+            }
+        }
+    }
+}
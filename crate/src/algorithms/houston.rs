@@ -0,0 +1,61 @@
+use crate::cells::ICellStrong;
+use crate::grid::{Grid};
+use crate::rng::RngWrapper;
+use crate::algorithms::{MazeAlgorithm, rand_element, link_cells, wilson::Wilson, steps::{GenerationEvent, GenerationObserver, position}};
+use std::collections::HashSet;
+use std::rc::{Rc};
+
+#[derive(Debug)]
+pub struct Houston {
+    fraction: f64
+}
+
+impl Houston {
+    /// `fraction` is the share of cells (0.0 to 1.0) the Aldous-Broder walk visits before switching to Wilson's.
+    pub fn new(fraction: f64) -> Houston {
+        Houston { fraction: fraction.max(0.0).min(1.0) }
+    }
+}
+
+impl Default for Houston {
+    fn default() -> Houston {
+        Houston::new(1.0 / 3.0)
+    }
+}
+
+/// Houston's Algorithm
+/// Aldous-Broder is quick while most cells are unvisited but crawls at the end,
+/// Wilson's is the opposite. Both produce uniform spanning trees, so:
+/// 1) Random walk as in Aldous-Broder until `fraction` of the cells have been visited
+/// 2) Finish the remaining cells with Wilson's loop-erased random walks
+impl MazeAlgorithm for Houston {
+    fn on_observed(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) {
+        let target = (grid.size() as f64 * self.fraction).ceil() as usize;
+        let mut cell = grid.random_cell(rng_generator).unwrap();
+        let mut visited: HashSet<(usize, usize)> = HashSet::new();
+        visited.insert((cell.borrow().row(), cell.borrow().column()));
+        observer.notify(GenerationEvent::Visit(position(&cell)));
+
+        while visited.len() < target {
+            let neighbors = cell.borrow().neighbors();
+            let neighbor = Rc::clone(rand_element(&neighbors, rng_generator));
+            let key = (neighbor.borrow().row(), neighbor.borrow().column());
+
+            if !visited.contains(&key) {
+                link_cells(&cell, &neighbor, observer);
+                visited.insert(key);
+            }
+
+            cell = neighbor;
+            observer.notify(GenerationEvent::Visit(position(&cell)));
+        }
+
+        let unvisited: Vec<ICellStrong> = grid.each_cell().iter()
+            .filter_map(|c| c.as_ref())
+            .filter(|c| !visited.contains(&(c.borrow().row(), c.borrow().column())))
+            .map(|c| Rc::clone(c))
+            .collect();
+
+        Wilson::walk_unvisited(unvisited, rng_generator, observer);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::algorithms::{rand_element, MazeAlgorithm};
+use crate::algorithms::{rand_element, link_cells, MazeAlgorithm, steps::{GenerationEvent, GenerationObserver, position}};
 use crate::cells::ICellStrong;
 use crate::grid::{Grid};
 use std::rc::{Rc};
@@ -13,10 +13,11 @@ pub struct HuntAndKill;
 /// 3) From top left corner of maze, scan left to right for the first unvisited cell
 /// 4) Link and continue until all cells are visited
 impl MazeAlgorithm for HuntAndKill {
-    fn on(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) {
+    fn on_observed(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) {
         let mut current = grid.random_cell(rng_generator);
         while current.is_some() {
             let c = current.clone().unwrap();
+            observer.notify(GenerationEvent::Visit(position(&c)));
             let unvisited_neighbors: Vec<ICellStrong> = c.borrow().neighbors()
                     .iter()
                     .filter(|&c| c.borrow().links().is_empty())
@@ -25,11 +26,11 @@ impl MazeAlgorithm for HuntAndKill {
             
             if !unvisited_neighbors.is_empty() {
                 let neighbor = rand_element(&unvisited_neighbors, rng_generator);
-                c.borrow_mut().link(Rc::clone(neighbor));
-                neighbor.borrow_mut().link(Rc::clone(&c));
+                link_cells(&c, neighbor, observer);
                 current = Some(neighbor.clone());
             }
             else {
+                observer.notify(GenerationEvent::Backtrack(position(&c)));
                 current = None;
 
                 for cell in grid.each_cell().iter() {
@@ -44,8 +45,7 @@ impl MazeAlgorithm for HuntAndKill {
                             current = Some(cell.clone());
 
                             let neighbor = rand_element(&visited_neighbors, rng_generator);
-                            cell.borrow_mut().link(Rc::clone(neighbor));
-                            neighbor.borrow_mut().link(Rc::clone(cell));
+                            link_cells(cell, neighbor, observer);
                             break;
                         }
                     }
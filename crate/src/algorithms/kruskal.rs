@@ -0,0 +1,187 @@
+use crate::cells::{ICellStrong, weave_cell::WeaveCell};
+use crate::grid::{Grid};
+use crate::rng::RngWrapper;
+use crate::algorithms::{MazeAlgorithm, shuffle, link_cells, steps::GenerationObserver};
+use std::collections::HashMap;
+use std::rc::{Rc};
+
+#[derive(Debug)]
+pub struct Kruskal;
+
+/// Randomized Kruskal's Algorithm
+/// 1) Put every cell in its own set and collect every pair of neighboring cells
+/// 2) Shuffle the pairs
+/// 3) For each pair, if the two cells are in different sets, link them and merge the sets
+/// Works on any grid since it only relies on `each_cell` and `neighbors`.
+impl MazeAlgorithm for Kruskal {
+    fn on_observed(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) {
+        let mut state = KruskalState::new(grid);
+        state.merge_remaining(rng_generator, observer);
+    }
+}
+
+#[derive(Debug)]
+pub struct WeaveKruskal;
+
+/// Kruskal's Algorithm for weave grids
+/// Plain Kruskal's never sees a passage it could tunnel under, since nothing is linked when it starts.
+/// So first try to lay down a crossing at as many random cells as the grid has, then carry on as usual.
+impl MazeAlgorithm for WeaveKruskal {
+    fn on_observed(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) {
+        let mut state = KruskalState::new(grid);
+        for _ in 0..grid.size() {
+            let cell = grid.random_cell(rng_generator).unwrap();
+            state.add_crossing(&cell, rng_generator, observer);
+        }
+        state.merge_remaining(rng_generator, observer);
+    }
+}
+
+/// Union-find over every cell in a grid, keyed by (row, column).
+pub struct KruskalState {
+    pub neighbors: Vec<(ICellStrong, ICellStrong)>,
+    parents: HashMap<(usize, usize), (usize, usize)>,
+    ranks: HashMap<(usize, usize), usize>,
+}
+
+impl KruskalState {
+    pub fn new(grid: &dyn Grid) -> KruskalState {
+        let mut state = KruskalState {
+            neighbors: vec![],
+            parents: HashMap::new(),
+            ranks: HashMap::new(),
+        };
+
+        for cell in grid.each_cell().iter() {
+            if let Some(cell) = cell {
+                let key = key_of(cell);
+                state.parents.insert(key, key);
+                state.ranks.insert(key, 0);
+
+                // Only keep each pair once, from the side with the smaller key.
+                for neighbor in cell.borrow().neighbors().iter() {
+                    if key < key_of(neighbor) {
+                        state.neighbors.push((Rc::clone(cell), Rc::clone(neighbor)));
+                    }
+                }
+            }
+        }
+
+        state
+    }
+
+    /// Like `new`, but with cells that already have passages between them starting out in the same set.
+    pub fn with_passages(grid: &dyn Grid) -> KruskalState {
+        let mut state = KruskalState::new(grid);
+        for cell in grid.each_cell().iter().filter_map(|c| c.as_ref()) {
+            for link in cell.borrow().links().iter().filter_map(|l| l.as_ref()) {
+                state.union(key_of(cell), key_of(link));
+            }
+        }
+        state
+    }
+
+    pub fn can_merge(&mut self, left: &ICellStrong, right: &ICellStrong) -> bool {
+        self.find(key_of(left)) != self.find(key_of(right))
+    }
+
+    /// Links the two cells and joins their sets.
+    pub fn merge(&mut self, left: &ICellStrong, right: &ICellStrong, observer: &mut dyn GenerationObserver) {
+        link_cells(left, right, observer);
+        self.union(key_of(left), key_of(right));
+    }
+
+    /// Merges every pair of neighbors that are still in different sets, in random order.
+    /// Running this after any partial carving turns it into a perfect maze.
+    pub fn merge_remaining(&mut self, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) {
+        // Pairs that are already joined can't change anything, so don't spend random numbers on them.
+        let mut neighbors = std::mem::replace(&mut self.neighbors, vec![]);
+        neighbors.retain(|(left, right)| self.can_merge(left, right));
+        self.neighbors = neighbors;
+        shuffle(&mut self.neighbors, rng_generator);
+
+        while let Some((left, right)) = self.neighbors.pop() {
+            if self.can_merge(&left, &right) {
+                self.merge(&left, &right, observer);
+            }
+        }
+    }
+
+    /// Turns an unvisited weave cell into a crossing: a passage through it one way and a tunnel under it the other way.
+    /// Only works when all four neighbors exist and neither pair is already joined. Returns whether it did.
+    pub fn add_crossing(&mut self, cell: &ICellStrong, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) -> bool {
+        let sides = {
+            let c = cell.borrow();
+            if !c.links().is_empty() {
+                return false;
+            }
+
+            match c.as_any().downcast_ref::<WeaveCell>() {
+                Some(c) => [&c.north, &c.south, &c.east, &c.west].iter()
+                    .map(|n| n.as_ref().map(|n| n.upgrade().unwrap() as ICellStrong))
+                    .collect::<Option<Vec<ICellStrong>>>(),
+                None => None
+            }
+        };
+
+        let sides = match sides {
+            Some(sides) => sides,
+            None => return false
+        };
+        let (north, south, east, west) = (&sides[0], &sides[1], &sides[2], &sides[3]);
+
+        if !self.can_merge(east, west) || !self.can_merge(north, south) {
+            return false;
+        }
+
+        let key = key_of(cell);
+        self.neighbors.retain(|(left, right)| key_of(left) != key && key_of(right) != key);
+
+        // Link the passage over the cell first, so the other pair can tunnel under it.
+        if rng_generator.gen_range(0, 2) == 0 {
+            self.merge(west, cell, observer);
+            self.merge(cell, east, observer);
+            self.merge(north, south, observer);
+        } else {
+            self.merge(north, cell, observer);
+            self.merge(cell, south, observer);
+            self.merge(west, east, observer);
+        }
+
+        true
+    }
+
+    fn find(&mut self, key: (usize, usize)) -> (usize, usize) {
+        let parent = self.parents[&key];
+        if parent == key {
+            return key;
+        }
+
+        let root = self.find(parent);
+        self.parents.insert(key, root);
+        root
+    }
+
+    fn union(&mut self, left: (usize, usize), right: (usize, usize)) {
+        let left = self.find(left);
+        let right = self.find(right);
+        if left == right {
+            return;
+        }
+
+        let (left_rank, right_rank) = (self.ranks[&left], self.ranks[&right]);
+        if left_rank < right_rank {
+            self.parents.insert(left, right);
+        } else {
+            self.parents.insert(right, left);
+            if left_rank == right_rank {
+                self.ranks.insert(left, left_rank + 1);
+            }
+        }
+    }
+}
+
+fn key_of(cell: &ICellStrong) -> (usize, usize) {
+    let c = cell.borrow();
+    (c.row(), c.column())
+}
@@ -1,20 +1,121 @@
-use crate::cells::ICellStrong;
+use crate::cells::{self, ICellStrong, cell::Cell, polar_cell::PolarCell, hex_cell::HexCell, triangle_cell::TriangleCell};
 use crate::grid::Grid;
 use std::fmt::Debug;
+use std::rc::Weak;
+use std::cell::RefCell;
 use crate::rng::RngWrapper;
 
 pub mod binary_tree;
-// pub mod sidewinder;
+pub mod sidewinder;
 pub mod aldous_broder;
 pub mod wilson;
 pub mod hunt_and_kill;
 pub mod recursive_backtracker;
+pub mod kruskal;
+pub mod prims;
+pub mod growing_tree;
+pub mod ellers;
+pub mod recursive_division;
+pub mod houston;
+pub mod steps;
+
+use self::steps::{GenerationEvent, GenerationObserver, NoObserver, position};
 
 pub trait MazeAlgorithm: Debug {
-    fn on(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>);
+    fn on(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) {
+        self.on_observed(grid, rng_generator, &mut NoObserver);
+    }
+
+    /// Same as `on`, but reports every visit, link and backtrack to the observer as it happens.
+    fn on_observed(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver);
 }
 
 pub fn rand_element<'a, T>(list: &'a [T], rng: &dyn RngWrapper<Shuffle=ICellStrong>) -> &'a T {
     let index = rng.gen_range(0, list.len());
     &list[index]
+}
+
+/// Links both cells to each other.
+pub fn link_cells(cell: &ICellStrong, other: &ICellStrong, observer: &mut dyn GenerationObserver) {
+    cells::link(cell, other);
+    observer.notify(GenerationEvent::Link(position(cell), position(other)));
+}
+
+/// Unlinks both cells from each other.
+pub fn unlink_cells(cell: &ICellStrong, other: &ICellStrong, observer: &mut dyn GenerationObserver) {
+    cells::unlink(cell, other);
+    observer.notify(GenerationEvent::Unlink(position(cell), position(other)));
+}
+
+/// Fisher-Yates shuffle driven by `gen_range`, for lists that aren't cells (`RngWrapper::shuffle` only takes cells).
+pub fn shuffle<T>(list: &mut Vec<T>, rng: &dyn RngWrapper<Shuffle=ICellStrong>) {
+    for i in (1..list.len()).rev() {
+        let j = rng.gen_range(0, i + 1);
+        list.swap(i, j);
+    }
+}
+
+/// The corner that `BinaryTree` and `Sidewinder` carve towards.
+/// On polar grids north and south both mean inward, since a cell can have several outward neighbors and the
+/// outer ring has none; east and west still pick clockwise or counter-clockwise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Corner {
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest
+}
+
+impl Default for Corner {
+    fn default() -> Corner {
+        Corner::NorthEast
+    }
+}
+
+/// Returns the (vertical, horizontal) neighbors of a cell in the direction of the bias corner, if they exist.
+/// "Horizontal" always stays in the same row of `grid.cells()`, so sidewinder runs can follow it.
+/// Triangle cells only have a vertical neighbor across their base, so only every other cell in a row has one.
+/// For polar cells vertical is always inward, and horizontal is clockwise or counter-clockwise by the corner,
+/// except where the ring wraps around.
+pub fn bias_neighbors(cell: &ICellStrong, corner: Corner) -> (Option<ICellStrong>, Option<ICellStrong>) {
+    let north = corner == Corner::NorthEast || corner == Corner::NorthWest;
+    let east = corner == Corner::NorthEast || corner == Corner::SouthEast;
+    let c = cell.borrow();
+
+    if let Some(c) = c.as_any().downcast_ref::<Cell>() {
+        let vertical = if north { &c.north } else { &c.south };
+        let horizontal = if east { &c.east } else { &c.west };
+        return (upgrade(vertical), upgrade(horizontal));
+    }
+
+    if let Some(c) = c.as_any().downcast_ref::<HexCell>() {
+        // Even columns sit half a cell higher, so the same-row neighbors are on the south diagonals.
+        let vertical = if north { &c.north } else { &c.south };
+        let horizontal = match (east, c.column % 2 == 0) {
+            (true, true) => &c.southeast,
+            (true, false) => &c.northeast,
+            (false, true) => &c.southwest,
+            (false, false) => &c.northwest,
+        };
+        return (upgrade(vertical), upgrade(horizontal));
+    }
+
+    if let Some(c) = c.as_any().downcast_ref::<TriangleCell>() {
+        let vertical = if north { &c.north } else { &c.south };
+        let horizontal = if east { &c.east } else { &c.west };
+        return (upgrade(vertical), upgrade(horizontal));
+    }
+
+    if let Some(c) = c.as_any().downcast_ref::<PolarCell>() {
+        let column = c.column;
+        let horizontal = upgrade(if east { &c.cw } else { &c.ccw })
+            .filter(|h| if east { h.borrow().column() > column } else { h.borrow().column() < column });
+        return (upgrade(&c.inward), horizontal);
+    }
+
+    (None, None)
+}
+
+fn upgrade<T: 'static + crate::cells::ICell>(cell: &Option<Weak<RefCell<T>>>) -> Option<ICellStrong> {
+    cell.as_ref().map(|c| c.upgrade().unwrap() as ICellStrong)
 }
\ No newline at end of file
@@ -0,0 +1,123 @@
+use crate::cells::ICellStrong;
+use crate::grid::{Grid};
+use crate::rng::RngWrapper;
+use crate::algorithms::{MazeAlgorithm, rand_element, link_cells, steps::{GenerationEvent, GenerationObserver, position}};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::rc::{Rc};
+
+#[derive(Debug)]
+pub struct SimplifiedPrims;
+
+/// Simplified Prim's Algorithm
+/// 1) Start with a random cell in the active set
+/// 2) Pick a random active cell and link it to a random unvisited neighbor, adding the neighbor to the active set
+/// 3) If the active cell has no unvisited neighbors, remove it from the active set
+/// Repeat until the active set is empty.
+impl MazeAlgorithm for SimplifiedPrims {
+    fn on_observed(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) {
+        let mut active: Vec<ICellStrong> = vec![grid.random_cell(rng_generator).unwrap()];
+
+        while !active.is_empty() {
+            let index = rng_generator.gen_range(0, active.len());
+            let cell = Rc::clone(&active[index]);
+            observer.notify(GenerationEvent::Visit(position(&cell)));
+            let available = unvisited_neighbors(&cell);
+
+            if available.is_empty() {
+                active.remove(index);
+                observer.notify(GenerationEvent::Backtrack(position(&cell)));
+            } else {
+                let neighbor = rand_element(&available, rng_generator);
+                link_cells(&cell, neighbor, observer);
+                active.push(Rc::clone(neighbor));
+            }
+        }
+    }
+}
+
+/// Supplies the weight of each cell for `TruePrims`. Called once per cell before the maze is carved.
+pub trait CostSource: Debug {
+    fn cost_of(&self, cell: &ICellStrong, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) -> u32;
+}
+
+/// Uniformly random costs in `0..max`, which is what gives true Prim's its radial texture.
+#[derive(Debug)]
+pub struct RandomCosts {
+    max: u32
+}
+
+impl RandomCosts {
+    /// A `max` of 0 leaves no costs to pick from, so it is raised to 1 and every cell costs 0.
+    pub fn new(max: u32) -> RandomCosts {
+        RandomCosts { max: max.max(1) }
+    }
+}
+
+impl CostSource for RandomCosts {
+    fn cost_of(&self, _cell: &ICellStrong, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) -> u32 {
+        rng_generator.gen_range(0, self.max as usize) as u32
+    }
+}
+
+#[derive(Debug)]
+pub struct TruePrims {
+    costs: Box<dyn CostSource>
+}
+
+impl TruePrims {
+    pub fn new(costs: Box<dyn CostSource>) -> TruePrims {
+        TruePrims { costs }
+    }
+}
+
+impl Default for TruePrims {
+    fn default() -> TruePrims {
+        TruePrims::new(Box::new(RandomCosts::new(100)))
+    }
+}
+
+/// "True" Prim's Algorithm
+/// Same as the simplified version, but every cell is given a cost up front.
+/// Instead of picking at random, always take the cheapest active cell and link it to its cheapest unvisited neighbor.
+impl MazeAlgorithm for TruePrims {
+    fn on_observed(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) {
+        let mut costs: HashMap<(usize, usize), u32> = HashMap::new();
+        for cell in grid.each_cell().iter() {
+            if let Some(cell) = cell {
+                let cost = self.costs.cost_of(cell, rng_generator);
+                costs.insert((cell.borrow().row(), cell.borrow().column()), cost);
+            }
+        }
+
+        let cost_of = |cell: &ICellStrong| costs[&(cell.borrow().row(), cell.borrow().column())];
+        let mut active: Vec<ICellStrong> = vec![grid.random_cell(rng_generator).unwrap()];
+
+        while !active.is_empty() {
+            let (index, cell) = active.iter()
+                .enumerate()
+                .min_by_key(|(_, c)| cost_of(c))
+                .map(|(i, c)| (i, Rc::clone(c)))
+                .unwrap();
+
+            observer.notify(GenerationEvent::Visit(position(&cell)));
+            let available = unvisited_neighbors(&cell);
+
+            if let Some(neighbor) = available.iter().min_by_key(|c| cost_of(c)) {
+                link_cells(&cell, neighbor, observer);
+                active.push(Rc::clone(neighbor));
+            } else {
+                active.remove(index);
+                observer.notify(GenerationEvent::Backtrack(position(&cell)));
+            }
+        }
+    }
+}
+
+fn unvisited_neighbors(cell: &ICellStrong) -> Vec<ICellStrong> {
+    cell.borrow().neighbors()
+        .iter()
+        .filter(|n| n.borrow().links().is_empty())
+        .map(|n| Rc::clone(n))
+        .collect()
+}
@@ -3,7 +3,7 @@ use std::rc::Rc;
 use crate::grid::{Grid};
 use crate::algorithms::rand_element;
 use crate::rng::RngWrapper;
-use crate::algorithms::MazeAlgorithm;
+use crate::algorithms::{MazeAlgorithm, link_cells, steps::{GenerationEvent, GenerationObserver, position}};
 
 #[derive(Debug)]
 pub struct RecursiveBacktracker;
@@ -14,7 +14,7 @@ pub struct RecursiveBacktracker;
 /// 3) When a cell with no unvisited neighbors is reached, pop stack until you get to a cell with unvisited neighbors
 /// 4) repeat until stack is empty
 impl MazeAlgorithm for RecursiveBacktracker {
-    fn on(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) {
+    fn on_observed(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) {
         let cells = grid.each_cell();
         let mut stack: Vec<ICellStrong> = vec![];
         let mut start = rand_element(&cells, rng_generator);
@@ -23,6 +23,7 @@ impl MazeAlgorithm for RecursiveBacktracker {
         }
 
         stack.push(start.clone().unwrap());
+        observer.notify(GenerationEvent::Visit(position(stack.last().unwrap())));
 
         while !stack.is_empty() {
             let current = stack.last().unwrap().clone();
@@ -35,12 +36,13 @@ impl MazeAlgorithm for RecursiveBacktracker {
 
             if !unvisited.is_empty() {
                 let rand_neighbor = rand_element(&unvisited, rng_generator);
-                current.borrow_mut().link(Rc::clone(rand_neighbor));
-                rand_neighbor.borrow_mut().link(Rc::clone(&current));
+                link_cells(&current, rand_neighbor, observer);
                 stack.push(Rc::clone(&rand_neighbor));
+                observer.notify(GenerationEvent::Visit(position(rand_neighbor)));
             }
             else {
                 stack.pop();
+                observer.notify(GenerationEvent::Backtrack(position(&current)));
             }
         }         
     }
@@ -0,0 +1,135 @@
+use crate::cells::{ICellStrong, cell::Cell};
+use crate::grid::{Grid};
+use crate::rng::RngWrapper;
+use crate::algorithms::{MazeAlgorithm, link_cells, unlink_cells, rand_element, kruskal::KruskalState, steps::{GenerationObserver, position}};
+
+#[derive(Debug)]
+pub struct RecursiveDivision {
+    room_size: usize
+}
+
+impl RecursiveDivision {
+    /// Regions smaller than `room_size` in both directions have a 1 in 4 chance of being left open as a room.
+    /// A `room_size` of 0 never leaves rooms, which gives a perfect maze.
+    pub fn new(room_size: usize) -> RecursiveDivision {
+        RecursiveDivision { room_size }
+    }
+
+    /// Whether the grid can be divided along its rows and columns: true for standard and masked grids only.
+    pub fn supports(grid: &dyn Grid) -> bool {
+        grid.each_cell().iter()
+            .filter_map(|c| c.as_ref())
+            .all(|c| c.borrow().as_any().is::<Cell>())
+    }
+}
+
+impl Default for RecursiveDivision {
+    fn default() -> RecursiveDivision {
+        RecursiveDivision::new(0)
+    }
+}
+
+/// Recursive Division Algorithm
+/// 1) Link every cell to all of its neighbors, leaving one big open room
+/// 2) Split the region with a wall along a random row or column, leaving a single passage through it
+/// 3) Repeat on both halves until regions are a single cell wide (or small enough to keep as a room)
+/// Only works on standard and masked grids, since it splits along rows and columns (see `supports`).
+/// Any other grid is left as it is. On masked grids, pieces of a region that voids wall off from
+/// the passage are joined back on through random neighbors afterwards.
+impl MazeAlgorithm for RecursiveDivision {
+    fn on_observed(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) {
+        let supported = RecursiveDivision::supports(grid);
+        debug_assert!(supported, "recursive division only works on standard and masked grids");
+        if !supported {
+            return;
+        }
+
+        for cell in grid.each_cell().iter() {
+            if let Some(cell) = cell {
+                let neighbors = cell.borrow().neighbors();
+                // Every pair comes up once from each side, so only link it from one of them.
+                for neighbor in neighbors.iter().filter(|n| position(cell) < position(n)) {
+                    link_cells(cell, neighbor, observer);
+                }
+            }
+        }
+
+        let cells = grid.cells();
+        self.divide(cells, 0, 0, cells.len(), cells.get(0).map_or(0, |r| r.len()), rng_generator, observer);
+
+        let mut state = KruskalState::with_passages(grid);
+        state.merge_remaining(rng_generator, observer);
+    }
+}
+
+impl RecursiveDivision {
+    fn divide(&self, cells: &[Vec<Option<ICellStrong>>], row: usize, column: usize, height: usize, width: usize,
+            rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) {
+        if height <= 1 || width <= 1 {
+            return;
+        }
+
+        if height < self.room_size && width < self.room_size && rng_generator.gen_range(0, 4) == 0 {
+            return;
+        }
+
+        if height > width {
+            self.divide_horizontally(cells, row, column, height, width, rng_generator, observer);
+        } else {
+            self.divide_vertically(cells, row, column, height, width, rng_generator, observer);
+        }
+    }
+
+    fn divide_horizontally(&self, cells: &[Vec<Option<ICellStrong>>], row: usize, column: usize, height: usize, width: usize,
+            rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) {
+        let divide_south_of = rng_generator.gen_range(0, height - 1);
+        let wall_row = row + divide_south_of;
+        let passage_at = passage(&cells[wall_row][column..column + width], &cells[wall_row + 1][column..column + width], rng_generator);
+
+        for x in 0..width {
+            if Some(x) != passage_at {
+                unlink(&cells[wall_row][column + x], &cells[wall_row + 1][column + x], observer);
+            }
+        }
+
+        self.divide(cells, row, column, divide_south_of + 1, width, rng_generator, observer);
+        self.divide(cells, row + divide_south_of + 1, column, height - divide_south_of - 1, width, rng_generator, observer);
+    }
+
+    fn divide_vertically(&self, cells: &[Vec<Option<ICellStrong>>], row: usize, column: usize, height: usize, width: usize,
+            rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) {
+        let divide_east_of = rng_generator.gen_range(0, width - 1);
+        let wall_column = column + divide_east_of;
+        let west: Vec<Option<ICellStrong>> = cells[row..row + height].iter().map(|r| r[wall_column].clone()).collect();
+        let east: Vec<Option<ICellStrong>> = cells[row..row + height].iter().map(|r| r[wall_column + 1].clone()).collect();
+        let passage_at = passage(&west, &east, rng_generator);
+
+        for y in 0..height {
+            if Some(y) != passage_at {
+                unlink(&cells[row + y][wall_column], &cells[row + y][wall_column + 1], observer);
+            }
+        }
+
+        self.divide(cells, row, column, height, divide_east_of + 1, rng_generator, observer);
+        self.divide(cells, row, column + divide_east_of + 1, height, width - divide_east_of - 1, rng_generator, observer);
+    }
+}
+
+fn unlink(cell: &Option<ICellStrong>, other: &Option<ICellStrong>, observer: &mut dyn GenerationObserver) {
+    if let (Some(cell), Some(other)) = (cell, other) {
+        unlink_cells(cell, other, observer);
+    }
+}
+
+/// Picks where to leave the passage through a wall between two lines of cells, skipping spots where a void
+/// is on either side. Returns None if there is nowhere to put it.
+fn passage(one_side: &[Option<ICellStrong>], other_side: &[Option<ICellStrong>], rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) -> Option<usize> {
+    let open: Vec<usize> = (0..one_side.len())
+        .filter(|&i| one_side[i].is_some() && other_side[i].is_some())
+        .collect();
+    if open.is_empty() {
+        None
+    } else {
+        Some(*rand_element(&open, rng_generator))
+    }
+}
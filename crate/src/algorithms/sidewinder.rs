@@ -1,39 +1,76 @@
-use crate::grid::{Grid, cell::Cell};
 use crate::rng::RngWrapper;
-use crate::grid::{cell::CellLinkStrong};
+use crate::grid::{Grid};
+use crate::cells::ICellStrong;
 use std::rc::{Rc};
-use crate::algorithms::{MazeAlgorithm, rand_element};
+use crate::algorithms::{MazeAlgorithm, Corner, bias_neighbors, rand_element, kruskal::KruskalState, steps::{GenerationEvent, GenerationObserver, position}};
 
-#[derive(Debug)]
-pub struct Sidewinder;
+#[derive(Debug, Default)]
+pub struct Sidewinder {
+    corner: Corner
+}
 
+impl Sidewinder {
+    pub fn new(corner: Corner) -> Sidewinder {
+        Sidewinder { corner }
+    }
+}
+
+/// Sidewinder Algorithm
+/// Walk each row towards the horizontal side of the bias corner, collecting cells into a run.
+/// 1) Randomly either link the current cell horizontally and keep the run going,
+/// 2) or close out the run by linking a random member that has a vertical neighbor, then start a new run.
+/// A run must close at the end of the row, and can't close early if the rest of the row would be left with no
+/// vertical neighbors to close on, like a lone triangle pointing the wrong way. Runs with no vertical neighbors
+/// at all, like cells boxed in by a mask, are joined on afterwards so the result is still perfect.
 impl MazeAlgorithm for Sidewinder {
-    fn on(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) {
+    fn on_observed(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) {
+        let mut state = KruskalState::new(grid);
+        let east = self.corner == Corner::NorthEast || self.corner == Corner::SouthEast;
+
         for row in grid.cells().iter() {
-            let mut run: Vec<CellLinkStrong> = vec![];
-        
-            for cell in row.iter() {
-                if let Some(cell) = cell {
-                    run.push(Rc::clone(&cell));
-                    let at_eastern_boundary = cell.borrow().east.is_none();
-                    let at_northern_boundary = cell.borrow().north.is_none();
-                    let should_close_out = at_eastern_boundary || (!at_northern_boundary && rng_generator.gen_range(0, 2) == 0);
-
-                    if should_close_out {
-                        let member = rand_element(&run, rng_generator).clone();
-
-                        if member.borrow().north.is_some() {
-                            let north = cell.borrow().north.clone().unwrap().upgrade().unwrap();
-                            Cell::link(Rc::clone(&cell), Rc::clone(&north), true);
+            let mut run: Vec<ICellStrong> = vec![];
+            let mut cells: Vec<&ICellStrong> = row.iter().filter_map(|c| c.as_ref()).collect();
+            if !east {
+                cells.reverse();
+            }
+
+            // Whether the rest of the run from each cell on could still be closed out, if a new run started there.
+            let mut can_close = vec![false; cells.len() + 1];
+            for (i, cell) in cells.iter().enumerate().rev() {
+                let (vertical, horizontal) = bias_neighbors(cell, self.corner);
+                can_close[i] = vertical.is_some() || (horizontal.is_some() && can_close[i + 1]);
+            }
+
+            for (i, cell) in cells.into_iter().enumerate() {
+                observer.notify(GenerationEvent::Visit(position(cell)));
+                run.push(Rc::clone(cell));
+
+                let (_, horizontal) = bias_neighbors(cell, self.corner);
+                let closers: Vec<(ICellStrong, ICellStrong)> = run.iter()
+                    .filter_map(|member| bias_neighbors(member, self.corner).0.map(|v| (Rc::clone(member), v)))
+                    .collect();
+
+                let should_close_out = horizontal.is_none()
+                    || (!closers.is_empty() && can_close[i + 1] && rng_generator.gen_range(0, 2) == 0);
+
+                if should_close_out {
+                    if !closers.is_empty() {
+                        let (member, vertical) = rand_element(&closers, rng_generator);
+                        if state.can_merge(member, vertical) {
+                            state.merge(member, vertical, observer);
                         }
-                        run.clear();
                     }
-                    else {
-                        let east = cell.borrow().east.clone().unwrap().upgrade().unwrap();
-                        Cell::link(Rc::clone(&cell), Rc::clone(&east), true);
-                    }                
+                    run.clear();
+                }
+                else {
+                    let horizontal = horizontal.unwrap();
+                    if state.can_merge(cell, &horizontal) {
+                        state.merge(cell, &horizontal, observer);
+                    }
                 }
             }
         }
+
+        state.merge_remaining(rng_generator, observer);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,58 @@
+use crate::cells::ICellStrong;
+use crate::grid::{Grid};
+use crate::rng::RngWrapper;
+use crate::algorithms::MazeAlgorithm;
+
+/// Something an algorithm did while carving a maze. Cells are given as (row, column).
+#[derive(Debug, Clone, PartialEq)]
+pub enum GenerationEvent {
+    /// The algorithm moved to, or started working from, this cell.
+    Visit((usize, usize)),
+    /// A passage was carved between the two cells.
+    Link((usize, usize), (usize, usize)),
+    /// A wall was put back between the two cells.
+    Unlink((usize, usize), (usize, usize)),
+    /// The algorithm is done with this cell, e.g. it was popped off a stack or dropped from the active set.
+    Backtrack((usize, usize)),
+}
+
+/// Receives every event as an algorithm produces it.
+pub trait GenerationObserver {
+    fn notify(&mut self, event: GenerationEvent);
+}
+
+/// Used by `MazeAlgorithm::on` when nobody is watching.
+pub struct NoObserver;
+
+impl GenerationObserver for NoObserver {
+    fn notify(&mut self, _event: GenerationEvent) {}
+}
+
+impl GenerationObserver for Vec<GenerationEvent> {
+    fn notify(&mut self, event: GenerationEvent) {
+        self.push(event);
+    }
+}
+
+/// Runs the algorithm on the grid and records every event, in the order they happened.
+/// The whole maze is carved before this returns; `replay` the events onto a blank grid of the same shape to animate it.
+pub fn record_events(alg: &dyn MazeAlgorithm, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) -> Vec<GenerationEvent> {
+    let mut events: Vec<GenerationEvent> = vec![];
+    alg.on_observed(grid, rng_generator, &mut events);
+    events
+}
+
+/// Applies a recorded event to the grid. Returns whether it changed a passage, which visits and backtracks don't.
+pub fn replay(grid: &dyn Grid, event: &GenerationEvent) -> bool {
+    let cell = |(row, column): (usize, usize)| grid.get_cell(row, column).unwrap();
+    match event {
+        GenerationEvent::Link(from, to) => grid.link(&cell(*from), &cell(*to)),
+        GenerationEvent::Unlink(from, to) => grid.unlink(&cell(*from), &cell(*to)),
+        _ => false
+    }
+}
+
+pub fn position(cell: &ICellStrong) -> (usize, usize) {
+    let c = cell.borrow();
+    (c.row(), c.column())
+}
@@ -1,14 +1,13 @@
 use crate::cells::ICellStrong;
 use crate::grid::{Grid};
 use crate::rng::RngWrapper;
-use crate::algorithms::{MazeAlgorithm, rand_element};
-use std::rc::{Rc};
+use crate::algorithms::{MazeAlgorithm, rand_element, link_cells, steps::{GenerationEvent, GenerationObserver, position}};
 
 #[derive(Debug)]
 pub struct Wilson;
 
 impl MazeAlgorithm for Wilson {
-    fn on(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) {
+    fn on_observed(&self, grid: &dyn Grid, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) {
         let mut unvisited: Vec<ICellStrong> = vec![];
 
         for cell in grid.each_cell().iter() {
@@ -20,15 +19,25 @@ impl MazeAlgorithm for Wilson {
         let first = rng_generator.gen_range(0, unvisited.len());
         unvisited.remove(first);
 
+        Wilson::walk_unvisited(unvisited, rng_generator, observer);
+    }
+}
+
+impl Wilson {
+    /// Carves loop-erased random walks from the unvisited cells until none are left.
+    /// Every cell not in `unvisited` is treated as already part of the maze, so at least one must be.
+    pub fn walk_unvisited(mut unvisited: Vec<ICellStrong>, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>, observer: &mut dyn GenerationObserver) {
         while !unvisited.is_empty() {
             let mut path: Vec<ICellStrong> = vec![];
 
             let mut cell = rand_element(&unvisited, rng_generator).clone();
             path.push(cell.clone());
+            observer.notify(GenerationEvent::Visit(position(&cell)));
 
             while unvisited.contains(&cell) {
                 let neighbors = cell.borrow().neighbors();
                 cell = rand_element(&neighbors, rng_generator).clone();
+                observer.notify(GenerationEvent::Visit(position(&cell)));
                 
                 if let Some(position) = path.iter().position(|c| c.borrow().row() == cell.borrow().row() && c.borrow().column() == cell.borrow().column()) {
                     path = path[0..=position].to_vec();
@@ -40,8 +49,7 @@ impl MazeAlgorithm for Wilson {
 
             let end = path.len() - 1;
             for i in 0..end {
-                path[i].borrow_mut().link(Rc::clone(&path[i + 1]));
-                path[i + 1].borrow_mut().link(Rc::clone(&path[i]));
+                link_cells(&path[i], &path[i + 1], observer);
                 unvisited.remove_item(&path[i]);
             }
         }
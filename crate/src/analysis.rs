@@ -0,0 +1,216 @@
+use crate::cells::{self, ICellStrong, polar_cell::PolarCell};
+use crate::grid::{Grid, braid::dead_ends};
+use crate::solvers::wall_follower::sides;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+/// Numbers that describe the character of a finished maze, for comparing algorithms.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MazeStats {
+    /// Cells that take part in the maze, including the under cells of a weave grid.
+    pub cells: usize,
+    pub dead_ends: usize,
+    /// Dead ends as a share of all cells.
+    pub dead_end_ratio: f64,
+    /// Passages on the shortest route from the entrance to the exit, or `None` if they aren't connected.
+    pub solution_length: Option<usize>,
+    /// Passages on the longest shortest route in the maze. Found with two sweeps, so only exact for perfect mazes.
+    pub diameter: usize,
+    /// Cells with three or more passages.
+    pub junctions: usize,
+    /// Junctions as a share of all cells. Higher means more choices along the way.
+    pub branching_factor: f64,
+    /// Cells off the solution route per dead end. Long, winding side branches ("rivers") score high,
+    /// lots of short stubs score low.
+    pub river_factor: f64,
+    /// Average number of passages in a run of cells between two junctions or dead ends.
+    pub average_corridor_length: f64,
+    /// Times the solution route doesn't carry straight on.
+    pub turns: usize,
+    /// Independent loops. Zero for a perfect maze; braiding adds one for each extra passage.
+    pub loops: usize,
+}
+
+/// Analyzes the maze with the first cell of the grid as the entrance and the last as the exit,
+/// e.g. the top left and bottom right corners of a standard grid. A grid without any cells gets all zeros.
+pub fn analyze(grid: &dyn Grid) -> MazeStats {
+    let all: Vec<ICellStrong> = grid.each_cell().into_iter().filter_map(|c| c).collect();
+    match (all.first(), all.last()) {
+        (Some(entrance), Some(exit)) => analyze_between(grid, entrance, exit),
+        _ => MazeStats::default()
+    }
+}
+
+pub fn analyze_between(grid: &dyn Grid, entrance: &ICellStrong, exit: &ICellStrong) -> MazeStats {
+    let all: Vec<ICellStrong> = grid.each_cell().into_iter().filter_map(|c| c).collect();
+    let dead_ends = dead_ends(&grid.each_cell()).len();
+    let junctions = all.iter().filter(|c| c.borrow().links().len() >= 3).count();
+    let passages = all.iter().fold(0, |acc, c| acc + c.borrow().links().len()) / 2;
+
+    let solution = route(entrance, exit);
+    let (far, _) = farthest(entrance);
+    let (_, diameter) = farthest(&far);
+
+    let off_route = solution.as_ref().map_or(all.len(), |route| all.len() - route.len());
+
+    MazeStats {
+        cells: all.len(),
+        dead_ends,
+        dead_end_ratio: ratio(dead_ends, all.len()),
+        solution_length: solution.as_ref().map(|route| route.len() - 1),
+        diameter,
+        junctions,
+        branching_factor: ratio(junctions, all.len()),
+        river_factor: ratio(off_route, dead_ends),
+        average_corridor_length: average_corridor_length(&all),
+        turns: solution.as_ref().map_or(0, |route| turns(route)),
+        loops: passages + components(&all) - all.len(),
+    }
+}
+
+fn ratio(count: usize, total: usize) -> f64 {
+    if total == 0 { 0.0 } else { count as f64 / total as f64 }
+}
+
+fn key_of(cell: &ICellStrong) -> (usize, usize) {
+    let c = cell.borrow();
+    (c.row(), c.column())
+}
+
+fn links_of(cell: &ICellStrong) -> Vec<ICellStrong> {
+    cell.borrow().links().into_iter().filter_map(|l| l).collect()
+}
+
+/// Breadth-first search from `from`, returning each reached cell with the cell it was reached from.
+fn flood(from: &ICellStrong) -> (Vec<ICellStrong>, HashMap<(usize, usize), ICellStrong>) {
+    let mut order = vec![Rc::clone(from)];
+    let mut parents: HashMap<(usize, usize), ICellStrong> = HashMap::new();
+    let mut seen: HashSet<(usize, usize)> = HashSet::new();
+    seen.insert(key_of(from));
+
+    let mut next = 0;
+    while next < order.len() {
+        let cell = Rc::clone(&order[next]);
+        for link in links_of(&cell) {
+            if seen.insert(key_of(&link)) {
+                parents.insert(key_of(&link), Rc::clone(&cell));
+                order.push(link);
+            }
+        }
+        next += 1;
+    }
+
+    (order, parents)
+}
+
+/// The cells on the shortest route, from `from` to `to` inclusive.
+fn route(from: &ICellStrong, to: &ICellStrong) -> Option<Vec<ICellStrong>> {
+    let (_, parents) = flood(from);
+    let mut route = vec![Rc::clone(to)];
+
+    while key_of(route.last().unwrap()) != key_of(from) {
+        let parent = parents.get(&key_of(route.last().unwrap()))?;
+        route.push(Rc::clone(parent));
+    }
+
+    route.reverse();
+    Some(route)
+}
+
+/// The last cell a flood from `from` reaches, and how many passages away it is.
+fn farthest(from: &ICellStrong) -> (ICellStrong, usize) {
+    let (order, parents) = flood(from);
+    let last = Rc::clone(order.last().unwrap());
+
+    let mut distance = 0;
+    let mut current = Rc::clone(&last);
+    while let Some(parent) = parents.get(&key_of(&current)) {
+        distance += 1;
+        current = Rc::clone(parent);
+    }
+
+    (last, distance)
+}
+
+fn components(all: &[ICellStrong]) -> usize {
+    let mut seen: HashSet<(usize, usize)> = HashSet::new();
+    let mut count = 0;
+
+    for cell in all.iter() {
+        if !seen.contains(&key_of(cell)) {
+            count += 1;
+            for reached in flood(cell).0.iter() {
+                seen.insert(key_of(reached));
+            }
+        }
+    }
+
+    count
+}
+
+/// Follows each passage out of every junction and dead end until the next one, counting the passages on the way.
+fn average_corridor_length(all: &[ICellStrong]) -> f64 {
+    let is_node = |cell: &ICellStrong| cell.borrow().links().len() != 2;
+    let mut walked: HashSet<((usize, usize), (usize, usize))> = HashSet::new();
+    let mut corridors = 0;
+    let mut total = 0;
+
+    for node in all.iter().filter(|c| is_node(c)) {
+        for first in links_of(node) {
+            if walked.contains(&(key_of(&first), key_of(node))) {
+                continue;
+            }
+
+            let mut previous = Rc::clone(node);
+            let mut current = first;
+            let mut length = 1;
+            while !is_node(&current) {
+                let next = links_of(&current).into_iter()
+                    .find(|l| key_of(l) != key_of(&previous))
+                    .unwrap();
+                previous = current;
+                current = next;
+                length += 1;
+            }
+
+            // Mark the far end so the same corridor isn't counted again from there.
+            walked.insert((key_of(&previous), key_of(&current)));
+            corridors += 1;
+            total += length;
+        }
+    }
+
+    if corridors == 0 { 0.0 } else { total as f64 / corridors as f64 }
+}
+
+fn turns(route: &[ICellStrong]) -> usize {
+    route.windows(3)
+        .filter(|w| !goes_straight(&w[1], &w[0], &w[2]))
+        .count()
+}
+
+/// Whether passing through `cell` from `from` to `to` carries straight on.
+/// On most grids that means leaving through the wall opposite the one you came in by.
+/// Triangles have no opposite walls, so every step through one is a turn.
+fn goes_straight(cell: &ICellStrong, from: &ICellStrong, to: &ICellStrong) -> bool {
+    let (from, to) = (key_of(from), key_of(to));
+
+    if let Some(c) = cell.borrow().as_any().downcast_ref::<PolarCell>() {
+        let is = |side: &Option<cells::polar_cell::PolarCellLinkWeak>, key: (usize, usize)| {
+            side.as_ref().and_then(|s| s.upgrade()).map_or(false, |s| (s.borrow().row, s.borrow().column) == key)
+        };
+        let outward = |key: (usize, usize)| c.outward.iter().any(|o| is(o, key));
+
+        return (is(&c.inward, from) && outward(to)) || (outward(from) && is(&c.inward, to))
+            || (is(&c.cw, from) && is(&c.ccw, to)) || (is(&c.ccw, from) && is(&c.cw, to));
+    }
+
+    let sides = sides(cell);
+    let count = sides.len();
+    let index_of = |key: (usize, usize)| sides.iter().position(|s| s.as_ref().map_or(false, |s| key_of(s) == key));
+
+    match (index_of(from), index_of(to)) {
+        (Some(from), Some(to)) if count % 2 == 0 => (from + count / 2) % count == to,
+        _ => false
+    }
+}
@@ -1,4 +1,4 @@
-use crate::cells::{ICellStrong, ICell};
+use crate::cells::{ICellStrong, ICell, remove_link};
 use std::rc::{Rc, Weak};
 use std::cell::{RefCell};
 use std::any::Any;
@@ -75,6 +75,10 @@ impl ICell for Cell {
             self.links.push(Some(_other));
         }
     }
+
+    fn unlink(&mut self, other: ICellStrong) {
+        remove_link(&mut self.links, &other);
+    }
 }
 
 impl Cell {
@@ -157,16 +161,4 @@ impl Cell {
         let _other: CellLinkWeak = Rc::downgrade(&Rc::clone(&nl.self_rc.upgrade().unwrap()));
         self.links.push(Some(_other));        
     }
-
-    // pub fn unlink(_self: CellLinkStrong, other: CellLinkStrong, bidir: bool) {
-    //     let index = _self.borrow().index_of_other(Rc::clone(&other));
-
-    //     if let Some(i) = index {
-    //         _self.borrow_mut().links.remove(i);
-    //     }
-
-    //     if bidir {
-    //         Cell::unlink(Rc::clone(&other), Rc::clone(&_self), false);
-    //     }
-    // }    
 }
\ No newline at end of file
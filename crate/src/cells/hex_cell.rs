@@ -2,7 +2,7 @@ use std::rc::Weak;
 use std::rc::Rc;
 use std::cell::RefCell;
 use std::any::Any;
-use crate::cells::{ICellStrong, ICell};
+use crate::cells::{ICellStrong, ICell, remove_link};
 
 pub type HexCellStrong = Rc<RefCell<HexCell>>;
 pub type HexCellWeak = Weak<RefCell<HexCell>>;
@@ -88,6 +88,10 @@ impl ICell for HexCell {
             self.links.push(Some(_other));
         }
     }
+
+    fn unlink(&mut self, other: ICellStrong) {
+        remove_link(&mut self.links, &other);
+    }
 }
 
 impl HexCell {
@@ -2,6 +2,7 @@ pub mod cell;
 pub mod polar_cell;
 pub mod hex_cell;
 pub mod triangle_cell;
+pub mod weave_cell;
 use std::any::Any;
 use std::rc::{Rc, Weak};
 use std::cell::RefCell;
@@ -14,6 +15,7 @@ pub trait ICell {
     fn neighbors(&self) -> Vec<ICellStrong>;
     fn links(&self) -> Vec<Option<ICellStrong>>;
     fn link(&mut self, other: ICellStrong);
+    fn unlink(&mut self, other: ICellStrong);
     fn as_any(&self) -> &dyn Any;
     fn row(&self) -> usize;
     fn column(&self) -> usize;
@@ -29,4 +31,37 @@ impl PartialEq for ICell {
     fn eq(&self, rhs: &ICell) -> bool {
         self.row() == rhs.row() && self.column() == rhs.column()
     }
-}
\ No newline at end of file
+}
+
+/// Links both cells to each other, keeping links symmetric.
+pub fn link(cell: &ICellStrong, other: &ICellStrong) {
+    cell.borrow_mut().link(Rc::clone(other));
+    other.borrow_mut().link(Rc::clone(cell));
+}
+
+/// Unlinks both cells from each other.
+pub fn unlink(cell: &ICellStrong, other: &ICellStrong) {
+    cell.borrow_mut().unlink(Rc::clone(other));
+    other.borrow_mut().unlink(Rc::clone(cell));
+}
+
+/// Drops `other` from a cell's own list of links. Every kind of cell keeps its links this way,
+/// so `ICell::unlink` is this for all of them.
+pub fn remove_link<C: ICell>(links: &mut Vec<Option<Weak<RefCell<C>>>>, other: &ICellStrong) {
+    let (other_row, other_col) = (other.borrow().row(), other.borrow().column());
+    links.retain(|l| {
+        let l = l.as_ref().unwrap().upgrade().unwrap();
+        let l = l.borrow();
+        !(l.row() == other_row && l.column() == other_col)
+    });
+}
+
+pub fn is_linked(cell: &ICellStrong, other: &ICellStrong) -> bool {
+    cell.borrow().links().iter()
+        .filter_map(|l| l.as_ref())
+        .any(|l| *l.borrow() == *other.borrow())
+}
+
+pub fn is_neighbor(cell: &ICellStrong, other: &ICellStrong) -> bool {
+    cell.borrow().neighbors().iter().any(|n| *n.borrow() == *other.borrow())
+}
@@ -1,5 +1,5 @@
 
-use crate::cells::{ICellStrong, ICell};
+use crate::cells::{ICellStrong, ICell, remove_link};
 use std::any::Any;
 use std::rc::{Rc, Weak};
 use std::cell::RefCell;
@@ -71,6 +71,10 @@ impl ICell for PolarCell {
         }
     }
 
+    fn unlink(&mut self, other: ICellStrong) {
+        remove_link(&mut self.links, &other);
+    }
+
     fn row(&self) -> usize {
         self.row
     }
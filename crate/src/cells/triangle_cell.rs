@@ -2,7 +2,7 @@ use std::rc::Weak;
 use std::rc::Rc;
 use std::cell::RefCell;
 use std::any::Any;
-use crate::cells::{ICellStrong, ICell};
+use crate::cells::{ICellStrong, ICell, remove_link};
 
 pub type TriangleCellStrong = Rc<RefCell<TriangleCell>>;
 pub type TriangleCellWeak = Weak<RefCell<TriangleCell>>;
@@ -80,6 +80,10 @@ impl ICell for TriangleCell {
             self.links.push(Some(_other));
         }
     }
+
+    fn unlink(&mut self, other: ICellStrong) {
+        remove_link(&mut self.links, &other);
+    }
 }
 
 impl TriangleCell {
@@ -0,0 +1,208 @@
+use std::rc::Weak;
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::any::Any;
+use crate::cells::{ICellStrong, ICell, remove_link};
+
+pub type WeaveCellStrong = Rc<RefCell<WeaveCell>>;
+pub type WeaveCellWeak = Weak<RefCell<WeaveCell>>;
+
+/// A standard grid cell that passages can tunnel under.
+/// Over cells make up the grid itself. Under cells are created on demand when a passage is linked
+/// straight through an over cell, and sit between the over cell's neighbors in its place.
+pub struct WeaveCell {
+    self_rc: WeaveCellWeak,
+    pub row: usize,
+    pub column: usize,
+    pub north: Option<WeaveCellWeak>,
+    pub south: Option<WeaveCellWeak>,
+    pub east: Option<WeaveCellWeak>,
+    pub west: Option<WeaveCellWeak>,
+    pub links: Vec<Option<WeaveCellWeak>>,
+    pub is_under: bool,
+    // Under cells report their row shifted by the grid height so they never share a (row, column) with the cell above them.
+    rows: usize,
+    under_cells: Weak<RefCell<Vec<WeaveCellStrong>>>,
+}
+
+impl PartialEq for WeaveCell {
+    fn eq(&self, other: &WeaveCell) -> bool {
+        self.row == other.row && self.column == other.column && self.is_under == other.is_under
+    }
+}
+
+impl ICell for WeaveCell {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn row(&self) -> usize {
+        if self.is_under {
+            self.row + self.rows
+        } else {
+            self.row
+        }
+    }
+
+    fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Over cells can also reach the cell on the far side of a neighbor that has a passage running across them.
+    fn neighbors(&self) -> Vec<ICellStrong> {
+        let mut vec: Vec<ICellStrong> = vec![];
+
+        for direction in 0..4 {
+            if let Some(neighbor) = step(self, direction) {
+                vec.push(Rc::clone(&neighbor) as ICellStrong);
+
+                if !self.is_under {
+                    if let Some(jump) = tunnel_target(&neighbor, direction) {
+                        vec.push(jump as ICellStrong);
+                    }
+                }
+            }
+        }
+
+        vec
+    }
+
+    fn links(&self) -> Vec<Option<ICellStrong>> {
+        self.links.iter()
+            .map(|c|
+                Some(c.as_ref().unwrap().upgrade().unwrap() as ICellStrong)
+            ).collect()
+    }
+
+    /// Linking to a cell two steps away tunnels under the cell in between.
+    /// Linking back from the far side afterwards is a no-op, since the under cell already joins them.
+    fn link(&mut self, other: ICellStrong) {
+        let other = match other.borrow().as_any().downcast_ref::<WeaveCell>() {
+            Some(nl) => nl.self_rc.upgrade().unwrap(),
+            None => return
+        };
+
+        for direction in 0..4 {
+            if let Some(neighbor) = step(self, direction) {
+                if Rc::ptr_eq(&neighbor, &other) {
+                    self.links.push(Some(Rc::downgrade(&other)));
+                    return;
+                }
+            }
+        }
+
+        for direction in 0..4 {
+            if let Some(middle) = step(self, direction) {
+                let beyond = step(&middle.borrow(), direction);
+                if beyond.map_or(false, |b| Rc::ptr_eq(&b, &other)) {
+                    if !middle.borrow().is_under {
+                        self.tunnel_under(&middle, &other, direction);
+                    }
+                    return;
+                }
+            }
+        }
+    }
+
+    fn unlink(&mut self, other: ICellStrong) {
+        remove_link(&mut self.links, &other);
+    }
+}
+
+/// The neighbor in the given direction: 0 north, 1 south, 2 east, 3 west.
+fn step(cell: &WeaveCell, direction: usize) -> Option<WeaveCellStrong> {
+    let neighbor = match direction {
+        0 => &cell.north,
+        1 => &cell.south,
+        2 => &cell.east,
+        _ => &cell.west,
+    };
+    neighbor.as_ref().map(|c| c.upgrade().unwrap())
+}
+
+/// The cell beyond `neighbor` if a passage can tunnel under it: the neighbor must be an over cell
+/// with a passage running across the tunnel and nothing linked along it.
+fn tunnel_target(neighbor: &WeaveCellStrong, direction: usize) -> Option<WeaveCellStrong> {
+    let n = neighbor.borrow();
+    if n.is_under {
+        return None;
+    }
+
+    let can_tunnel = if direction < 2 { n.horizontal_passage() } else { n.vertical_passage() };
+    if can_tunnel { step(&n, direction) } else { None }
+}
+
+impl WeaveCell {
+    pub fn new(row: usize, column: usize, rows: usize, under_cells: Weak<RefCell<Vec<WeaveCellStrong>>>) -> WeaveCellStrong {
+        let c = WeaveCell {
+            row, column,
+            north: None,
+            south: None,
+            east: None,
+            west: None,
+            links: Vec::new(),
+            is_under: false,
+            rows,
+            under_cells,
+            self_rc: Weak::new(),
+        };
+
+        let rc = Rc::new(RefCell::new(c));
+        rc.borrow_mut().self_rc = Rc::downgrade(&rc);
+
+        rc
+    }
+
+    pub fn is_linked(&self, other: &WeaveCellStrong) -> bool {
+        self.links.iter().any(|l| Rc::ptr_eq(&l.as_ref().unwrap().upgrade().unwrap(), other))
+    }
+
+    pub fn is_linked_to(&self, other: &Option<WeaveCellWeak>) -> bool {
+        if let Some(other) = other {
+            self.is_linked(&other.upgrade().unwrap())
+        } else {
+            false
+        }
+    }
+
+    pub fn horizontal_passage(&self) -> bool {
+        self.is_linked_to(&self.east) && self.is_linked_to(&self.west)
+            && !self.is_linked_to(&self.north) && !self.is_linked_to(&self.south)
+    }
+
+    pub fn vertical_passage(&self) -> bool {
+        self.is_linked_to(&self.north) && self.is_linked_to(&self.south)
+            && !self.is_linked_to(&self.east) && !self.is_linked_to(&self.west)
+    }
+
+    /// Puts a new under cell beneath `middle`, between this cell and `other`, and links it to both.
+    /// `direction` is the direction of `middle` as seen from this cell, as in `step`.
+    fn tunnel_under(&mut self, middle: &WeaveCellStrong, other: &WeaveCellStrong, direction: usize) {
+        let (row, column) = (middle.borrow().row, middle.borrow().column);
+        let under = WeaveCell::new(row, column, self.rows, Weak::clone(&self.under_cells));
+        under.borrow_mut().is_under = true;
+
+        let this = Weak::clone(&self.self_rc);
+        let far = Rc::downgrade(other);
+        {
+            let mut u = under.borrow_mut();
+            let mut o = other.borrow_mut();
+            let down = Some(Rc::downgrade(&under));
+            match direction {
+                0 => { self.north = down.clone(); o.south = down; u.south = Some(this.clone()); u.north = Some(far.clone()); },
+                1 => { self.south = down.clone(); o.north = down; u.north = Some(this.clone()); u.south = Some(far.clone()); },
+                2 => { self.east = down.clone(); o.west = down; u.west = Some(this.clone()); u.east = Some(far.clone()); },
+                _ => { self.west = down.clone(); o.east = down; u.east = Some(this.clone()); u.west = Some(far.clone()); },
+            }
+
+            u.links.push(Some(this));
+            u.links.push(Some(far));
+            o.links.push(Some(Rc::downgrade(&under)));
+        }
+        self.links.push(Some(Rc::downgrade(&under)));
+
+        if let Some(under_cells) = self.under_cells.upgrade() {
+            under_cells.borrow_mut().push(under);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use crate::grid::{Grid, distances::Distances};
+use crate::cells::{ICellStrong, cell::Cell, hex_cell::HexCell, polar_cell::PolarCell, triangle_cell::TriangleCell, weave_cell::WeaveCell};
+use std::collections::{HashMap, BinaryHeap};
+use std::cmp::Reverse;
+use std::rc::Rc;
+
+/// A* search from `start` to `goal` along the maze's passages.
+/// Returns the path in the same form as `Distances::path_to`: every cell on it, keyed by its distance from `start`.
+/// Returns `None` if the goal can't be reached.
+pub fn astar(grid: &dyn Grid, start: &ICellStrong, goal: &ICellStrong) -> Option<Distances> {
+    let start_key = key_of(start);
+    let goal_key = key_of(goal);
+
+    let mut pending: BinaryHeap<Reverse<(u32, u32, (usize, usize))>> = BinaryHeap::new();
+    let mut costs: HashMap<(usize, usize), u32> = HashMap::new();
+    let mut came_from: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+    let mut cells: HashMap<(usize, usize), ICellStrong> = HashMap::new();
+
+    costs.insert(start_key, 0);
+    cells.insert(start_key, Rc::clone(start));
+    pending.push(Reverse((estimate(grid, start, goal), 0, start_key)));
+
+    while let Some(Reverse((_, cost, key))) = pending.pop() {
+        if key == goal_key {
+            let mut path = Distances::new(start, false);
+            let mut current = key;
+            while current != start_key {
+                path.insert(current.0, current.1, costs[&current]);
+                current = came_from[&current];
+            }
+            return Some(path);
+        }
+
+        if cost > costs[&key] {
+            // Already expanded through a shorter route.
+            continue;
+        }
+
+        let links = cells[&key].borrow().links();
+        for link in links.iter().filter_map(|l| l.as_ref()) {
+            let link_key = key_of(link);
+            let link_cost = cost + 1;
+
+            if costs.get(&link_key).map_or(true, |c| link_cost < *c) {
+                costs.insert(link_key, link_cost);
+                came_from.insert(link_key, key);
+                cells.insert(link_key, Rc::clone(link));
+                pending.push(Reverse((link_cost + estimate(grid, link, goal), link_cost, link_key)));
+            }
+        }
+    }
+
+    None
+}
+
+/// A lower bound on the number of steps between two cells, suited to the grid they are on.
+/// Falls back to 0, which turns A* into plain Dijkstra, for cells it doesn't know.
+pub fn estimate(grid: &dyn Grid, from: &ICellStrong, to: &ICellStrong) -> u32 {
+    let (a, b) = (from.borrow(), to.borrow());
+    let (a, b) = (a.as_any(), b.as_any());
+
+    if let (Some(a), Some(b)) = (a.downcast_ref::<Cell>(), b.downcast_ref::<Cell>()) {
+        manhattan((a.row, a.column), (b.row, b.column))
+    } else if let (Some(a), Some(b)) = (a.downcast_ref::<TriangleCell>(), b.downcast_ref::<TriangleCell>()) {
+        manhattan((a.row, a.column), (b.row, b.column))
+    } else if let (Some(a), Some(b)) = (a.downcast_ref::<WeaveCell>(), b.downcast_ref::<WeaveCell>()) {
+        // Under cells sit in the same spot as the cell above them, so use the unshifted row.
+        manhattan((a.row, a.column), (b.row, b.column))
+    } else if let (Some(a), Some(b)) = (a.downcast_ref::<HexCell>(), b.downcast_ref::<HexCell>()) {
+        hex_distance((a.row, a.column), (b.row, b.column))
+    } else if let (Some(a), Some(b)) = (a.downcast_ref::<PolarCell>(), b.downcast_ref::<PolarCell>()) {
+        polar_distance(grid, (a.row, a.column), (b.row, b.column))
+    } else {
+        0
+    }
+}
+
+fn manhattan(a: (usize, usize), b: (usize, usize)) -> u32 {
+    (diff(a.0, b.0) + diff(a.1, b.1)) as u32
+}
+
+/// Odd columns are shifted half a cell south, so convert to cube coordinates first.
+fn hex_distance(a: (usize, usize), b: (usize, usize)) -> u32 {
+    let cube = |(row, column): (usize, usize)| {
+        let x = column as i64;
+        let z = row as i64 - (x - (x & 1)) / 2;
+        (x, -x - z, z)
+    };
+
+    let (a, b) = (cube(a), cube(b));
+    (a.0 - b.0).abs().max((a.1 - b.1).abs()).max((a.2 - b.2).abs()) as u32
+}
+
+/// A path whose innermost ring is `k` has to travel down to `k` and back out, and has to cover the angle between
+/// the two cells, one cell at a time, on rings no narrower than `k`. The cheapest `k` gives the bound.
+fn polar_distance(grid: &dyn Grid, a: (usize, usize), b: (usize, usize)) -> u32 {
+    let cells = grid.cells();
+    let ring_size = |row: usize| cells[row].len() as f64;
+    let span = |(row, column): (usize, usize)| (column as f64 / ring_size(row), (column + 1) as f64 / ring_size(row));
+
+    let (a_span, b_span) = (span(a), span(b));
+    let overlaps = |x: (f64, f64), y: (f64, f64)| (y.0 - x.0).rem_euclid(1.0) < x.1 - x.0;
+    let gap = if overlaps(a_span, b_span) || overlaps(b_span, a_span) {
+        0.0
+    } else {
+        (b_span.0 - a_span.1).rem_euclid(1.0).min((a_span.0 - b_span.1).rem_euclid(1.0))
+    };
+
+    let radial = diff(a.0, b.0);
+    let innermost = a.0.min(b.0);
+    (0..=innermost)
+        .map(|k| {
+            let down_and_up = radial + 2 * (innermost - k);
+            // Allow for rounding so the bound never overshoots.
+            let around = (gap * ring_size(k) - 1e-9).floor().max(0.0) as usize;
+            down_and_up.max(around)
+        })
+        .min()
+        .unwrap_or(radial) as u32
+}
+
+fn diff(a: usize, b: usize) -> usize {
+    if a > b { a - b } else { b - a }
+}
+
+fn key_of(cell: &ICellStrong) -> (usize, usize) {
+    let c = cell.borrow();
+    (c.row(), c.column())
+}
@@ -0,0 +1,74 @@
+/// Something a maze can be drawn onto. Each grid describes itself in these few primitives in `Grid::draw`,
+/// so a new output format only needs a new backend.
+///
+/// Coordinates are in pixels with (0, 0) at the top left, and angles are in radians clockwise from east.
+/// Walls are drawn in black. Colors are CSS colors like those `CellFormatter::background_color` returns,
+/// and an empty color, like a cell `DistanceGrid` never reached, means the shape is left unfilled.
+pub trait Backend {
+    /// Called once, before anything is drawn, with the size of the whole drawing.
+    fn begin(&mut self, width: f64, height: f64);
+    /// Fills a polygon.
+    fn shape(&mut self, points: &[(f64, f64)], color: &str);
+    /// Fills the part of a ring between two angles, like a polar cell.
+    fn sector(&mut self, cx: f64, cy: f64, inner: f64, outer: f64, from: f64, to: f64, color: &str);
+    /// Draws a straight wall.
+    fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64);
+    /// Draws a wall along a circle between two angles. A whole circle runs from 0 to 2π.
+    fn arc(&mut self, cx: f64, cy: f64, r: f64, from: f64, to: f64);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Primitive {
+    Shape { points: Vec<(f64, f64)>, color: String },
+    Sector { center: (f64, f64), inner: f64, outer: f64, from: f64, to: f64, color: String },
+    Line { from: (f64, f64), to: (f64, f64) },
+    Arc { center: (f64, f64), radius: f64, from: f64, to: f64 },
+}
+
+/// Remembers everything drawn on it, in order, so rendering can be checked without a browser or an image.
+#[derive(Debug, Default)]
+pub struct Recorder {
+    /// The size passed to `begin`, if it's been called.
+    pub size: Option<(f64, f64)>,
+    pub primitives: Vec<Primitive>,
+}
+
+impl Recorder {
+    pub fn new() -> Recorder {
+        Recorder::default()
+    }
+
+    pub fn lines(&self) -> Vec<&Primitive> {
+        self.primitives.iter().filter(|p| if let Primitive::Line { .. } = p { true } else { false }).collect()
+    }
+
+    /// The filled shapes and sectors.
+    pub fn fills(&self) -> Vec<&Primitive> {
+        self.primitives.iter().filter(|p| match p {
+            Primitive::Shape { .. } | Primitive::Sector { .. } => true,
+            _ => false
+        }).collect()
+    }
+}
+
+impl Backend for Recorder {
+    fn begin(&mut self, width: f64, height: f64) {
+        self.size = Some((width, height));
+    }
+
+    fn shape(&mut self, points: &[(f64, f64)], color: &str) {
+        self.primitives.push(Primitive::Shape { points: points.to_vec(), color: String::from(color) });
+    }
+
+    fn sector(&mut self, cx: f64, cy: f64, inner: f64, outer: f64, from: f64, to: f64, color: &str) {
+        self.primitives.push(Primitive::Sector { center: (cx, cy), inner, outer, from, to, color: String::from(color) });
+    }
+
+    fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64) {
+        self.primitives.push(Primitive::Line { from: (x1, y1), to: (x2, y2) });
+    }
+
+    fn arc(&mut self, cx: f64, cy: f64, r: f64, from: f64, to: f64) {
+        self.primitives.push(Primitive::Arc { center: (cx, cy), radius: r, from, to });
+    }
+}
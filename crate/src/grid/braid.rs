@@ -0,0 +1,52 @@
+use crate::algorithms::rand_element;
+use crate::cells::{self, ICellStrong};
+use crate::rng::RngWrapper;
+use std::rc::Rc;
+
+/// Cells with exactly one passage.
+pub fn dead_ends(cells: &[Option<ICellStrong>]) -> Vec<ICellStrong> {
+    cells.iter()
+        .filter_map(|c| c.as_ref())
+        .filter(|c| c.borrow().links().len() == 1)
+        .map(|c| Rc::clone(c))
+        .collect()
+}
+
+/// Joins each dead end to one of its unlinked neighbors with probability `p`.
+/// Neighbors that are dead ends themselves are preferred, since that clears two at once.
+/// Only relies on `neighbors` and `links`, so it works the same on every grid.
+pub fn braid(cells: &[Option<ICellStrong>], p: f64, rng: &dyn RngWrapper<Shuffle=ICellStrong>) {
+    let mut dead_ends = dead_ends(cells);
+    rng.shuffle(&mut dead_ends);
+
+    for cell in dead_ends.iter() {
+        // An earlier pass may already have linked this cell to a neighboring dead end.
+        if cell.borrow().links().len() != 1 || rng.gen_f64() >= p {
+            continue;
+        }
+
+        let unlinked = unlinked_neighbors(cell);
+        if unlinked.is_empty() {
+            continue;
+        }
+
+        let mut best: Vec<ICellStrong> = unlinked.iter()
+            .filter(|n| n.borrow().links().len() == 1)
+            .map(|n| Rc::clone(n))
+            .collect();
+
+        if best.is_empty() {
+            best = unlinked;
+        }
+
+        let neighbor = rand_element(&best, rng);
+        cells::link(cell, neighbor);
+    }
+}
+
+fn unlinked_neighbors(cell: &ICellStrong) -> Vec<ICellStrong> {
+    let neighbors = cell.borrow().neighbors();
+    neighbors.into_iter()
+        .filter(|n| !cells::is_linked(cell, n))
+        .collect()
+}
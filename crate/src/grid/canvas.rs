@@ -1,13 +1,14 @@
 use wasm_bindgen::{JsCast, prelude::*};
 use web_sys::{HtmlElement, Node, HtmlCanvasElement, CanvasRenderingContext2d};
-use super::{GridType, triangle_grid::TRIANGLE_GRID, hex_grid::HEX_GRID, polar_grid::POLAR_GRID, standard_grid::STANDARD_GRID};
+use super::{GridType, backend::Backend, triangle_grid::TRIANGLE_GRID, hex_grid::HEX_GRID, polar_grid::POLAR_GRID, standard_grid::STANDARD_GRID, weave_grid::WEAVE_GRID};
 
 pub fn cleanup_canvas(grid_type: &GridType) {
     match grid_type {
         GridType::StandardGrid => remove_old_canvas(STANDARD_GRID),
         GridType::PolarGrid => remove_old_canvas(POLAR_GRID),
         GridType::HexGrid => remove_old_canvas(HEX_GRID),
-        GridType::TriangleGrid => remove_old_canvas(TRIANGLE_GRID)
+        GridType::TriangleGrid => remove_old_canvas(TRIANGLE_GRID),
+        GridType::WeaveGrid => remove_old_canvas(WEAVE_GRID)
     }            
 }
 
@@ -62,7 +63,7 @@ pub fn draw_line(ctx: &CanvasRenderingContext2d, x1: f64, y1: f64, x2: f64, y2:
     ctx.stroke();
 }
 
-pub fn draw_shape(ctx: &CanvasRenderingContext2d, xys: Vec<(f64,f64)>, color: &str) {
+pub fn draw_shape(ctx: &CanvasRenderingContext2d, xys: &[(f64,f64)], color: &str) {
     if xys.is_empty() {
         return;
     }
@@ -87,4 +88,68 @@ pub fn draw_shape(ctx: &CanvasRenderingContext2d, xys: Vec<(f64,f64)>, color: &s
 pub enum DrawMode {
     Line,
     Background
-}
\ No newline at end of file
+}
+
+/// Draws onto a new canvas on the page, replacing the one with the same id.
+pub struct CanvasBackend {
+    element_id: &'static str,
+    context: Option<CanvasRenderingContext2d>,
+}
+
+impl CanvasBackend {
+    pub fn new(element_id: &'static str) -> CanvasBackend {
+        CanvasBackend { element_id, context: None }
+    }
+
+    fn context(&self) -> &CanvasRenderingContext2d {
+        self.context.as_ref().expect("begin must be called before drawing")
+    }
+}
+
+impl Backend for CanvasBackend {
+    fn begin(&mut self, width: f64, height: f64) {
+        remove_old_canvas(self.element_id);
+        let context = setup_grid_canvas(self.element_id).unwrap();
+        set_canvas_size(self.element_id, width as usize, height as usize);
+        context.set_fill_style(&JsValue::from_str("black"));
+        context.set_stroke_style(&JsValue::from_str("black"));
+        self.context = Some(context);
+    }
+
+    fn shape(&mut self, points: &[(f64, f64)], color: &str) {
+        if !color.is_empty() {
+            draw_shape(self.context(), points, color);
+        }
+    }
+
+    fn sector(&mut self, cx: f64, cy: f64, inner: f64, outer: f64, from: f64, to: f64, color: &str) {
+        if color.is_empty() {
+            return;
+        }
+
+        let context = self.context();
+        context.set_fill_style(&JsValue::from_str(color));
+        context.set_stroke_style(&JsValue::from_str(color));
+
+        context.begin_path();
+        // arc(x, y, radius, startAngle, endAngle, anticlockwise)
+        context.arc(cx, cy, inner, from, to).unwrap();
+        context.arc_with_anticlockwise(cx, cy, outer, to, from, true).unwrap();
+
+        context.fill();
+        context.stroke();
+        context.set_fill_style(&JsValue::from_str("black"));
+        context.set_stroke_style(&JsValue::from_str("black"));
+    }
+
+    fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64) {
+        draw_line(self.context(), x1, y1, x2, y2);
+    }
+
+    fn arc(&mut self, cx: f64, cy: f64, r: f64, from: f64, to: f64) {
+        let context = self.context();
+        context.begin_path();
+        context.arc(cx, cy, r, from, to).unwrap();
+        context.stroke();
+    }
+}
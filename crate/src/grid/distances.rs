@@ -1,22 +1,48 @@
 use crate::grid::{Grid, CellFormatter};
 use crate::cells::{ICellStrong};
-use std::collections::HashMap;
+use std::collections::{HashMap, BinaryHeap};
+use std::cmp::Reverse;
 use std::rc::{Rc};
 use std::char;
 use math::round;
 
+/// The cost of moving into each cell, for hazards like lava or mud. Cells without a weight cost 1.
+#[derive(Debug, Clone, Default)]
+pub struct Weights {
+    // (row, column)
+    costs: HashMap<(usize, usize), u32>
+}
+
+impl Weights {
+    pub fn new() -> Weights {
+        Weights::default()
+    }
+
+    /// Costs below 1 are raised to 1. Free steps would leave linked cells at the same distance,
+    /// and `path_to` could walk back and forth between them forever.
+    pub fn set(&mut self, row: usize, column: usize, cost: u32) {
+        self.costs.insert((row, column), cost.max(1));
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> u32 {
+        *self.costs.get(&(row, column)).unwrap_or(&1)
+    }
+}
+
 #[derive(Debug)]
 pub struct Distances {
     // (row, column)
     cells: HashMap<(usize, usize), u32>,
-    root: (usize, usize)
+    root: (usize, usize),
+    weights: Weights
 }
 
 impl Distances {
     pub fn new(cell: &ICellStrong, build_distances: bool) -> Distances {
         let mut d = Distances {
             cells: HashMap::new(),
-            root: (cell.borrow().row(), cell.borrow().column())
+            root: (cell.borrow().row(), cell.borrow().column()),
+            weights: Weights::new()
         };
         d.insert(cell.borrow().row(), cell.borrow().column(), 0);
         if build_distances {
@@ -26,10 +52,24 @@ impl Distances {
         d
     }
 
+    /// Distances from `cell` where each step costs the weight of the cell it moves into, rather than 1.
+    pub fn weighted(cell: &ICellStrong, weights: Weights) -> Distances {
+        let mut d = Distances {
+            cells: HashMap::new(),
+            root: (cell.borrow().row(), cell.borrow().column()),
+            weights
+        };
+        d.insert(cell.borrow().row(), cell.borrow().column(), 0);
+        d.build_weighted_distances(cell);
+
+        d
+    }
+
     fn new_from_root(&self) -> Distances {
         Distances {
             cells: HashMap::new(),
-            root: (self.root.0, self.root.1)
+            root: (self.root.0, self.root.1),
+            weights: self.weights.clone()
         }
     }
 
@@ -73,6 +113,36 @@ impl Distances {
         }
     }
 
+    // Dijkstra's algorithm with a priority queue, so the cheapest cell is always settled next.
+    // Only matters for braided mazes; in a perfect maze there is one route to each cell anyway.
+    pub fn build_weighted_distances(&mut self, root: &ICellStrong) {
+        let mut pending: BinaryHeap<Reverse<(u32, (usize, usize))>> = BinaryHeap::new();
+        let mut cells: HashMap<(usize, usize), ICellStrong> = HashMap::new();
+        let root_key = (root.borrow().row(), root.borrow().column());
+        cells.insert(root_key, Rc::clone(root));
+        pending.push(Reverse((0, root_key)));
+
+        while let Some(Reverse((distance, key))) = pending.pop() {
+            if distance > *self.get_distance(key.0, key.1).unwrap() {
+                // Already settled through a cheaper route.
+                continue;
+            }
+
+            let links = cells[&key].borrow().links();
+            for link in links.iter().filter_map(|l| l.as_ref()) {
+                let link_key = (link.borrow().row(), link.borrow().column());
+                let total = distance + self.weights.get(link_key.0, link_key.1);
+
+                if self.get_distance(link_key.0, link_key.1).map_or(true, |d| total < *d) {
+                    self.insert(link_key.0, link_key.1, total);
+                    cells.insert(link_key, Rc::clone(link));
+                    pending.push(Reverse((total, link_key)));
+                }
+            }
+        }
+    }
+
+    /// Walks back from `goal` to the root along the cheapest route.
     pub fn path_to(&self, goal: &ICellStrong, grid: &dyn Grid) -> Distances {
         let mut current: ICellStrong = Rc::clone(goal);
         let mut breadcrumbs = self.new_from_root();       
@@ -80,6 +150,7 @@ impl Distances {
 
         while !(current.borrow().row() == self.root.0 && current.borrow().column() == self.root.1) {
             let current_distance = *self.get_distance(current.borrow().row(), current.borrow().column()).unwrap();
+            let step_cost = self.weights.get(current.borrow().row(), current.borrow().column());
             let mut next_current: ICellStrong = grid.new_cell(0, 0);
 
             for n in current.borrow().links().iter() {
@@ -87,7 +158,7 @@ impl Distances {
                     let n_ref = n.borrow();
 
                     let neighbor_distance = *self.get_distance(n_ref.row(), n_ref.column()).unwrap();
-                    if neighbor_distance < current_distance {
+                    if neighbor_distance + step_cost == current_distance {
                         breadcrumbs.insert(n_ref.row(), n_ref.column(), neighbor_distance);
                         next_current = Rc::clone(&n);
                         break;
@@ -143,6 +214,21 @@ impl DistanceGrid {
         }
     }
 
+    /// Colors and paths by the cheapest total cost from `root` instead of by the number of steps.
+    pub fn weighted(root: &ICellStrong, weights: Weights) -> DistanceGrid {
+        let distances = Distances::weighted(root, weights);
+
+        if distances.max().1 == 0 {
+            panic!("Max distance from root cell should not be zero. Make sure an algorithm was applied to the grid before initializing the distance grid.");
+        }
+
+        DistanceGrid {
+            distances,
+            path_grid: Distances::new(root, false),
+            show_path_only: false,
+        }
+    }
+
     pub fn build_path_to(&mut self, cell: &ICellStrong, grid: &dyn Grid) {
         self.path_grid = self.distances.path_to(cell, grid);
     }
@@ -155,7 +241,7 @@ impl DistanceGrid {
         let (max_cell, _) = self.distances.max();
 
         let new_root = grid.get_cell(max_cell.0, max_cell.1).unwrap();
-        let new_distances = Distances::new(&new_root, true);
+        let new_distances = Distances::weighted(&new_root, self.distances.weights.clone());
 
         let (new_max_cell, _) = new_distances.max();
         
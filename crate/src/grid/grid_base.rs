@@ -1,12 +1,17 @@
-use crate::algorithms::rand_element;
 use std::rc::{Rc};
-use wbg_rand::{Rng, wasm_rng};
-use wasm_bindgen::{prelude::JsValue, JsCast};
-use crate::grid::{CellFormatter, standard_grid::STANDARD_GRID, canvas::{setup_grid_canvas, DrawMode, draw_line, remove_old_canvas, set_canvas_size, draw_shape}};
-use crate::cells::{ICellStrong, cell::{CellLinkStrong}, ICell};
+use crate::grid::{CellFormatter, backend::Backend, text::centered, raster::parse_color, canvas::DrawMode};
+use crate::cells::{self, ICellStrong, cell::{CellLinkStrong}};
 use crate::rng::RngWrapper;
 use crate::cells::cell::Cell;
-// Not sure how to get around explicitly using wasm_rng.
+
+/// How much of a maze `sparsify` cuts away.
+#[derive(Debug, Clone, Copy)]
+pub enum Sparsity {
+    /// Removes every dead end this many times over. Each pass shortens every dead-end corridor by one cell.
+    Passes(usize),
+    /// Removes dead ends in random order until at most this fraction (0.0 to 1.0) of the cells is left.
+    Remaining(f64),
+}
 
 #[derive(Debug)]
 pub struct GridBase {
@@ -97,7 +102,7 @@ impl GridBase {
                         // 2) If we aren't in the last column
                         if j < self.columns - 1 {
                             // 3) Check if the next cell to the right or the cell to the south east is present and render the corner
-                            if self.cells[i][j+1].is_some() || self.cells[i+1][j+1].is_some() {
+                            if self.cells[i][j+1].is_some() || (i < self.rows - 1 && self.cells[i+1][j+1].is_some()) {
                                 bottom += "   +";                                    
                             } else {
                                 bottom += "    ";    
@@ -250,6 +255,79 @@ impl GridBase {
             .collect()
     }
 
+    /// Cuts a cell out of the grid, leaving a void like a masked-out cell.
+    /// Its passages and its neighbors' references to it are removed along with it.
+    pub fn remove_cell(&mut self, row: usize, column: usize) {
+        let cell = match self.get_cell_link_strong(row, column) {
+            Some(cell) => cell,
+            None => return
+        };
+
+        let ics = Rc::clone(&cell) as ICellStrong;
+        let links = ics.borrow().links();
+        for link in links.iter().filter_map(|l| l.as_ref()) {
+            cells::unlink(&ics, link);
+        }
+
+        {
+            let c = cell.borrow();
+            if let Some(north) = c.north.as_ref().and_then(|n| n.upgrade()) {
+                north.borrow_mut().south = None;
+            }
+            if let Some(south) = c.south.as_ref().and_then(|n| n.upgrade()) {
+                south.borrow_mut().north = None;
+            }
+            if let Some(east) = c.east.as_ref().and_then(|n| n.upgrade()) {
+                east.borrow_mut().west = None;
+            }
+            if let Some(west) = c.west.as_ref().and_then(|n| n.upgrade()) {
+                west.borrow_mut().east = None;
+            }
+        }
+
+        self.cells[row][column] = None;
+        if let Some(cells) = self.cells_.as_mut() {
+            cells[row][column] = None;
+        }
+    }
+
+    /// Culls dead ends, cutting them out of the grid entirely, to leave a sparser maze.
+    /// Returns the (row, column) of every cell removed, in the order they went.
+    pub fn sparsify(&mut self, sparsity: Sparsity, rng: &dyn RngWrapper<Shuffle=ICellStrong>) -> Vec<(usize, usize)> {
+        let mut remaining = self.each_cell().iter().filter(|c| c.is_some()).count();
+        let (passes, target) = match sparsity {
+            Sparsity::Passes(passes) => (passes, 0),
+            Sparsity::Remaining(fraction) => (usize::max_value(), (remaining as f64 * fraction.max(0.0).min(1.0)).ceil() as usize)
+        };
+
+        let mut removed = vec![];
+        for _ in 0..passes {
+            let mut dead_ends = self.dead_ends();
+            if dead_ends.is_empty() {
+                break;
+            }
+            rng.shuffle(&mut dead_ends);
+
+            for cell in dead_ends.iter() {
+                if remaining <= target {
+                    return removed;
+                }
+
+                // Removing a neighbor earlier in the pass may have left this cell with no passages at all.
+                if cell.borrow().links().len() != 1 {
+                    continue;
+                }
+
+                let (row, column) = (cell.borrow().row(), cell.borrow().column());
+                self.remove_cell(row, column);
+                removed.push((row, column));
+                remaining -= 1;
+            }
+        }
+
+        removed
+    }
+
     // pub fn dead_ends(&self) -> Vec<CellLinkStrong> {
     //     self.each_std_cell().iter()
     //         .filter(|c| {
@@ -267,101 +345,176 @@ impl GridBase {
 
     
 
-    pub fn braid(&self, p: f64, rng: &dyn RngWrapper<Shuffle=ICellStrong>) {
-        let mut dead_ends = self.dead_ends();
-        rng.shuffle(&mut dead_ends);
-
-        for cell in to_cls(&dead_ends).iter() {
-            let rand: f64 = wasm_rng().gen_range(0_f64, 1_f64);
-
-            if cell.borrow().links.len() != 1 || p > rand {
-                continue;
-            }
-
-            let best = {
-                let cell = cell.borrow();
-                let neighbors = cell.neighbors_std();
-                let neighbors: Vec<CellLinkStrong> = neighbors.iter().filter(|c| {
-                    let c = Rc::clone(c);
-                    !cell.is_linked(c)
-                }).map(|c| Rc::clone(c)).collect();
-
-                let mut best: Vec<CellLinkStrong> = neighbors.iter()
-                    .filter(|c| c.borrow().links.len() == 1)
-                    .map(|x| Rc::clone(x))
-                    .collect();
-
-                if best.is_empty() {
-                    best = neighbors;
-                }
-
-                best
-            };            
-
-            let neighbor_to_link: CellLinkStrong = rand_element(&best, rng).clone();
-
-            // web_sys::console::log_1(&JsValue::from_str(&format!("borrowing")));
-            // web_sys::console::log_1(&JsValue::from_str(&format!("borrowed")));
-            // web_sys::console::log_1(&JsValue::from_str(&format!("last time")));
-            // web_sys::console::log_1(&JsValue::from_str(&format!("done")));
-
-            cell.borrow_mut().link(neighbor_to_link.clone());
-            neighbor_to_link.borrow_mut().link(cell.clone());
-        }
-    }
-    
-
-    pub fn to_web(&self, formatter: &dyn CellFormatter, colorize: bool) {
-        remove_old_canvas(STANDARD_GRID);
-        let context = setup_grid_canvas(STANDARD_GRID).unwrap();
-        let size = 15;
-        
-        set_canvas_size(STANDARD_GRID, size * self.columns, size * self.rows);
-        let size = size as f64;
-        context.set_fill_style(&JsValue::from_str("black"));
-        context.set_stroke_style(&JsValue::from_str("black"));
+    pub fn draw(&self, backend: &mut dyn Backend, formatter: &dyn CellFormatter, colorize: bool, size: f64) {
+        backend.begin(size * self.columns as f64, size * self.rows as f64);
 
         for mode in [DrawMode::Background, DrawMode::Line].iter() {
-            for cell in self.each_std_cell() {    
+            for cell in self.each_std_cell() {
                 if let Some(cell) = cell {
-
                     let x1 = (cell.borrow().column as f64) * size;
                     let y1 = (cell.borrow().row as f64) * size;
-                    let x2 = (cell.borrow().column as f64) * size + size;
-                    let y2 = (cell.borrow().row as f64) * size + size;
-
+                    let x2 = x1 + size;
+                    let y2 = y1 + size;
 
                     match mode {
-                        DrawMode::Background => { 
+                        DrawMode::Background => {
                             if colorize {
-                                let points = vec![(x1, y1), (x2, y1), (x2, y2), (x1, y2)];
-                                let ics: ICellStrong =  Rc::clone(&cell) as ICellStrong;
-                                let color = formatter.background_color(&ics);
-                                draw_shape(&context, points, &color);
+                                let ics: ICellStrong = Rc::clone(&cell) as ICellStrong;
+                                backend.shape(&[(x1, y1), (x2, y1), (x2, y2), (x1, y2)], &formatter.background_color(&ics));
                             }
                         },
                         DrawMode::Line => {
-                            if cell.borrow().north.is_none() {
-                                draw_line(&context, x1, y1, x2, y1);
+                            let c = cell.borrow();
+                            if c.north.is_none() {
+                                backend.line(x1, y1, x2, y1);
                             }
-                            
-                            if cell.borrow().west.is_none() {
-                                draw_line(&context, x1, y1, x1, y2);
+                            if c.west.is_none() {
+                                backend.line(x1, y1, x1, y2);
                             }
-
-                            if cell.borrow().is_not_linked(&cell.borrow().east) {
-                                draw_line(&context, x2, y1, x2, y2);
+                            if c.is_not_linked(&c.east) {
+                                backend.line(x2, y1, x2, y2);
                             }
-
-                            if cell.borrow().is_not_linked(&cell.borrow().south) {
-                                draw_line(&context, x1, y2, x2, y2);
+                            if c.is_not_linked(&c.south) {
+                                backend.line(x1, y2, x2, y2);
                             }
                         }
-                    };
+                    }
+                }
+            }
+        }
+    }
+
+    /// Draws the maze with Unicode box-drawing characters, picking the right junction at every corner,
+    /// which reads much better than `to_string` in a terminal. Each cell shows the formatter's `contents_of`.
+    /// With `colorize`, cells are shaded with its `background_color` using 24-bit ANSI colors.
+    pub fn to_unicode(&self, formatter: &dyn CellFormatter, colorize: bool) -> String {
+        let rows = self.cells.len() as isize;
+        let columns = self.cells.first().map_or(0, |row| row.len()) as isize;
+        let cell = |i: isize, j: isize| -> Option<CellLinkStrong> {
+            if i < 0 || j < 0 {
+                return None;
+            }
+            self.cells.get(i as usize).and_then(|row| row.get(j as usize)).and_then(|c| c.clone())
+        };
+        // Whether there's a wall between two spots, either of which may be off the grid or masked out.
+        let wall = |a: Option<CellLinkStrong>, b: Option<CellLinkStrong>| match (a, b) {
+            (Some(a), Some(b)) => !a.borrow().is_linked(b),
+            (None, None) => false,
+            _ => true
+        };
+
+        let mut lines = Vec::new();
+        for i in 0..=rows {
+            let mut line = String::new();
+            for j in 0..=columns {
+                let up = wall(cell(i - 1, j - 1), cell(i - 1, j));
+                let down = wall(cell(i, j - 1), cell(i, j));
+                let left = wall(cell(i - 1, j - 1), cell(i, j - 1));
+                let right = wall(cell(i - 1, j), cell(i, j));
+                line.push(junction(up, down, left, right));
+                if j < columns {
+                    line += if right { "───" } else { "   " };
+                }
+            }
+            lines.push(line);
+
+            if i == rows {
+                break;
+            }
+
+            let mut line = String::new();
+            for j in 0..=columns {
+                line.push(if wall(cell(i, j - 1), cell(i, j)) { '│' } else { ' ' });
+                if let Some(c) = cell(i, j) {
+                    let ics: ICellStrong = c as ICellStrong;
+                    let body = centered(&formatter.contents_of(&ics), 3);
+                    match parse_color(&formatter.background_color(&ics)).filter(|_| colorize) {
+                        Some([r, g, b, _]) => {
+                            // Dark text on light cells and light text on dark ones.
+                            let light = 0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64 >= 128.0;
+                            let text = if light { "0;0;0" } else { "255;255;255" };
+                            line += &format!("\x1b[48;2;{};{};{}m\x1b[38;2;{}m{}\x1b[0m", r, g, b, text, body);
+                        },
+                        None => line += &body
+                    }
+                } else if j < columns {
+                    line += "   ";
                 }
             }
+            lines.push(line);
         }
+
+        lines.iter().map(|l| l.trim_end().to_string() + "\n").collect()
+    }
+}
+
+/// The box-drawing character for a corner with walls leading off in the given directions.
+fn junction(up: bool, down: bool, left: bool, right: bool) -> char {
+    match (up, down, left, right) {
+        (false, false, false, false) => ' ',
+        (true, false, false, false) => '╵',
+        (false, true, false, false) => '╷',
+        (false, false, true, false) => '╴',
+        (false, false, false, true) => '╶',
+        (true, true, false, false) => '│',
+        (false, false, true, true) => '─',
+        (false, true, false, true) => '┌',
+        (false, true, true, false) => '┐',
+        (true, false, false, true) => '└',
+        (true, false, true, false) => '┘',
+        (true, true, false, true) => '├',
+        (true, true, true, false) => '┤',
+        (false, true, true, true) => '┬',
+        (true, false, true, true) => '┴',
+        (true, true, true, true) => '┼',
+    }
+}
+
+/// Which sides of a square cell are open passages.
+pub struct Passages {
+    pub north: bool,
+    pub south: bool,
+    pub east: bool,
+    pub west: bool,
+}
+
+/// The walls of a square cell shrunk by `inset` on every side, with open passages drawn as corridors out to the
+/// cell's edge, as (x1, y1, x2, y2). The gap this leaves between walls is what lets a passage visibly cross over
+/// or under another. Cells running under another only draw their corridor stubs, so pass `draw_walls: false` for them.
+pub fn inset_cell_lines(x: f64, y: f64, size: f64, inset: f64, passages: &Passages, draw_walls: bool) -> Vec<(f64, f64, f64, f64)> {
+    let (x1, x2, x3, x4) = (x, x + inset, x + size - inset, x + size);
+    let (y1, y2, y3, y4) = (y, y + inset, y + size - inset, y + size);
+    let mut lines = vec![];
+
+    if passages.north {
+        lines.push((x2, y1, x2, y2));
+        lines.push((x3, y1, x3, y2));
+    } else if draw_walls {
+        lines.push((x2, y2, x3, y2));
     }
+
+    if passages.south {
+        lines.push((x2, y3, x2, y4));
+        lines.push((x3, y3, x3, y4));
+    } else if draw_walls {
+        lines.push((x2, y3, x3, y3));
+    }
+
+    if passages.west {
+        lines.push((x1, y2, x2, y2));
+        lines.push((x1, y3, x2, y3));
+    } else if draw_walls {
+        lines.push((x2, y2, x2, y3));
+    }
+
+    if passages.east {
+        lines.push((x3, y2, x4, y2));
+        lines.push((x3, y3, x4, y3));
+    } else if draw_walls {
+        lines.push((x3, y2, x3, y3));
+    }
+
+    lines
 }
 
 pub fn to_cls(vec: &Vec<ICellStrong>) -> Vec<CellLinkStrong> {
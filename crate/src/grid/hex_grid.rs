@@ -1,6 +1,5 @@
 use std::rc::{Rc};
-use wasm_bindgen::prelude::JsValue;
-use super::{Grid, CellFormatter, canvas::{remove_old_canvas, setup_grid_canvas, draw_line, DrawMode, draw_shape, set_canvas_size}};
+use super::{Grid, CellFormatter, backend::Backend, text::{TextCanvas, centered}, canvas::{CanvasBackend, DrawMode}};
 use crate::rng::RngWrapper;
 use crate::cells::{ICellStrong, hex_cell::{HexCellStrong, HexCellWeak, HexCell}};
 
@@ -71,8 +70,51 @@ impl Grid for HexGrid {
         None
     }
 
-    fn to_string(&self, _contents: &dyn CellFormatter) -> String {
-        "".to_string()
+    /// Draws flat-topped hexagons, with every other column shifted down half a cell as on the canvas:
+    ///
+    /// ```text
+    ///  ___     ___
+    /// / 0 \___/ 4 \
+    /// \   / 2 \   /
+    /// / 1       3 \
+    /// \___/ 3 \___/
+    ///     \___/
+    /// ```
+    fn to_string(&self, contents: &dyn CellFormatter) -> String {
+        let height = 2 * self.rows + if self.columns > 1 { 2 } else { 1 };
+        let mut text = TextCanvas::new(4 * self.columns + 1, height);
+
+        for cell in self.each_hex_cell().iter() {
+            if let Some(cell) = cell {
+                let c = cell.borrow();
+                let x = 4 * c.column;
+                let top = 2 * c.row + c.column % 2;
+
+                if is_not_linked(cell, &c.north) {
+                    text.put(top, x + 1, "___");
+                }
+                if is_not_linked(cell, &c.northwest) {
+                    text.put(top + 1, x, "/");
+                }
+                if is_not_linked(cell, &c.northeast) {
+                    text.put(top + 1, x + 4, "\\");
+                }
+                if is_not_linked(cell, &c.southwest) {
+                    text.put(top + 2, x, "\\");
+                }
+                if is_not_linked(cell, &c.south) {
+                    text.put(top + 2, x + 1, "___");
+                }
+                if is_not_linked(cell, &c.southeast) {
+                    text.put(top + 2, x + 4, "/");
+                }
+
+                let ics: ICellStrong = Rc::clone(cell) as ICellStrong;
+                text.put(top + 1, x + 1, &centered(&contents.contents_of(&ics), 3));
+            }
+        }
+
+        text.finish()
     }
 
     fn size(&self) -> usize {
@@ -80,28 +122,18 @@ impl Grid for HexGrid {
     }
 
 
-    fn braid(&self, p: f64, rng: &dyn RngWrapper<Shuffle=ICellStrong>) {
-        // TODO: not yet implemented
-    }
-
     fn to_web(&self, formatter: &dyn CellFormatter, colorize: bool) {
-        let size = 20;
+        self.draw(&mut CanvasBackend::new(HEX_GRID), formatter, colorize, self.cell_size());
+    }
 
-        let size = f64::from(size);
-        
+    fn draw(&self, backend: &mut dyn Backend, formatter: &dyn CellFormatter, colorize: bool, size: f64) {
         let a_size = size / 2_f64;
         let b_size = size * 3_f64.sqrt() / 2_f64;
-        // let width = size * 2_f64;
         let height = b_size * 2_f64;
 
-        let img_width = (3_f64 * a_size * (self.columns as f64) + a_size + 0.5_f64).trunc() as usize;
-        let img_height = (height * (self.rows as f64) + b_size + 0.5_f64).trunc() as usize;
-        
-        remove_old_canvas(HEX_GRID);
-        let context = setup_grid_canvas(HEX_GRID).unwrap();
-        set_canvas_size(HEX_GRID, img_width, img_height);
-        context.set_fill_style(&JsValue::from_str("black"));
-        context.set_stroke_style(&JsValue::from_str("black"));
+        let img_width = (3_f64 * a_size * (self.columns as f64) + a_size + 0.5_f64).trunc();
+        let img_height = (height * (self.rows as f64) + b_size + 0.5_f64).trunc();
+        backend.begin(img_width, img_height);
 
         for mode in [DrawMode::Background, DrawMode::Line].iter() {
             for cell in self.each_hex_cell().iter() {
@@ -127,35 +159,29 @@ impl Grid for HexGrid {
                     match mode {
                         DrawMode::Background => {
                             if colorize {
-                                let points = vec![(x_fw, y_m), (x_nw, y_n), (x_ne, y_n), (x_fe, y_m), (x_ne, y_s), (x_nw, y_s)];
-                                let ics: ICellStrong =  Rc::clone(cell) as ICellStrong;
-                                let color = formatter.background_color(&ics);
-                                draw_shape(&context, points, &color);
+                                let points = [(x_fw, y_m), (x_nw, y_n), (x_ne, y_n), (x_fe, y_m), (x_ne, y_s), (x_nw, y_s)];
+                                let ics: ICellStrong = Rc::clone(cell) as ICellStrong;
+                                backend.shape(&points, &formatter.background_color(&ics));
                             }
                         },
                         DrawMode::Line => {
                             if cell.borrow().southwest.is_none() {
-                                draw_line(&context, x_fw, y_m, x_nw, y_s);
+                                backend.line(x_fw, y_m, x_nw, y_s);
                             }
-
                             if cell.borrow().northwest.is_none() {
-                                draw_line(&context, x_fw, y_m, x_nw, y_n);
+                                backend.line(x_fw, y_m, x_nw, y_n);
                             }
-
                             if cell.borrow().north.is_none() {
-                                draw_line(&context, x_nw, y_n, x_ne, y_n);
+                                backend.line(x_nw, y_n, x_ne, y_n);
                             }
-
                             if is_not_linked(cell, &cell.borrow().northeast) {
-                                draw_line(&context, x_ne, y_n, x_fe, y_m);
+                                backend.line(x_ne, y_n, x_fe, y_m);
                             }
-                            
                             if is_not_linked(cell, &cell.borrow().southeast) {
-                                draw_line(&context, x_fe, y_m, x_ne, y_s);
+                                backend.line(x_fe, y_m, x_ne, y_s);
                             }
-
                             if is_not_linked(cell, &cell.borrow().south) {
-                                draw_line(&context, x_ne, y_s, x_nw, y_s);
+                                backend.line(x_ne, y_s, x_nw, y_s);
                             }
                         }
                     }
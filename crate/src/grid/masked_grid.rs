@@ -1,6 +1,6 @@
 use std::rc::Rc;
 use std::cell::RefCell;
-use super::{Grid, mask::Mask, CellFormatter, grid_base::GridBase};
+use super::{Grid, mask::Mask, CellFormatter, backend::Backend, canvas::CanvasBackend, standard_grid::STANDARD_GRID, grid_base::{GridBase, Sparsity}};
 use crate::cells::{ICellStrong, cell::{CellLinkStrong, Cell}};
 use crate::rng::RngWrapper;
 
@@ -24,7 +24,21 @@ impl MaskedGrid {
         masked_grid.prepare_grid();
         masked_grid.grid.configure_cells();
         masked_grid
-    }    
+    }
+
+    /// Draws the maze with box-drawing characters for a terminal. See `GridBase::to_unicode`.
+    pub fn to_unicode(&self, formatter: &dyn CellFormatter, colorize: bool) -> String {
+        self.grid.to_unicode(formatter, colorize)
+    }
+
+    /// Culls dead ends and masks off the cells they leave behind. See `GridBase::sparsify`.
+    pub fn sparsify(&mut self, sparsity: Sparsity, rng: &dyn RngWrapper<Shuffle=ICellStrong>) -> Vec<(usize, usize)> {
+        let removed = self.grid.sparsify(sparsity, rng);
+        for (row, column) in removed.iter() {
+            self.mask.borrow_mut().set(*row, *column, false);
+        }
+        removed
+    }
 }
 
 impl Grid for MaskedGrid {
@@ -83,11 +97,15 @@ impl Grid for MaskedGrid {
         self.mask.borrow().count()
     }
 
-    fn braid(&self, p: f64, rng: &dyn RngWrapper<Shuffle=ICellStrong>) {
-        self.grid.braid(p, rng);
+    fn to_web(&self, formatter: &dyn CellFormatter, colorize: bool) {
+        self.draw(&mut CanvasBackend::new(STANDARD_GRID), formatter, colorize, self.cell_size());
     }
 
-    fn to_web(&self, formatter: &dyn CellFormatter, colorize: bool) {
-        self.grid.to_web(formatter, colorize);
+    fn draw(&self, backend: &mut dyn Backend, formatter: &dyn CellFormatter, colorize: bool, size: f64) {
+        self.grid.draw(backend, formatter, colorize, size);
+    }
+
+    fn cell_size(&self) -> f64 {
+        15.0
     }
 }
\ No newline at end of file
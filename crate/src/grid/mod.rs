@@ -1,5 +1,8 @@
 use crate::rng::RngWrapper;
-use crate::cells::{ICellStrong};
+use crate::cells::{self, ICellStrong};
+use backend::Backend;
+use svg::Svg;
+use raster::{Raster, RasterOptions};
 
 pub mod distances;
 pub mod mask;
@@ -8,9 +11,18 @@ pub mod masked_grid;
 pub mod standard_grid;
 pub mod grid_base;
 pub mod canvas;
+pub mod backend;
 pub mod polar_grid;
 pub mod hex_grid;
 pub mod triangle_grid;
+pub mod weave_grid;
+pub mod braid;
+pub mod astar;
+pub mod parse;
+pub mod svg;
+pub mod text;
+pub mod raster;
+pub mod png;
 
 pub trait CellFormatter {
     fn contents_of(&self, cell: &ICellStrong) -> String;
@@ -27,9 +39,60 @@ pub trait Grid {
     fn cells(&self) -> &Vec<Vec<Option<ICellStrong>>>;
     fn get_cell(&self, row: usize, column: usize) -> Option<ICellStrong>;
     fn to_string(&self, contents: &dyn CellFormatter) -> String;
+    /// How many cells `each_cell` returns, leaving out voids.
     fn size(&self) -> usize;
     fn to_web(&self, formatter: &dyn CellFormatter, colorize: bool);
-    fn braid(&self, p: f64, rng: &dyn RngWrapper<Shuffle=ICellStrong>);
+    /// Draws the maze onto `backend`, with cells `size` pixels across, or rings `size` pixels deep on a polar grid.
+    /// With `colorize`, cells are filled with the formatter's `background_color` before any walls are drawn.
+    fn draw(&self, backend: &mut dyn Backend, formatter: &dyn CellFormatter, colorize: bool, size: f64);
+
+    /// How big `to_web` and `to_svg` draw each cell.
+    fn cell_size(&self) -> f64 {
+        20.0
+    }
+
+    /// Draws the maze as a standalone SVG document, colored like `to_web`.
+    fn to_svg(&self, formatter: &dyn CellFormatter, colorize: bool) -> String {
+        let mut svg = Svg::new();
+        self.draw(&mut svg, formatter, colorize, self.cell_size());
+        svg.finish()
+    }
+
+    /// Draws the maze into an RGBA image without needing a browser. `Raster::to_png` encodes it.
+    fn to_raster(&self, formatter: &dyn CellFormatter, options: &RasterOptions) -> Raster {
+        let mut raster = Raster::new(options);
+        self.draw(&mut raster, formatter, options.colorize, options.cell_size);
+        raster
+    }
+
+    /// Carves a passage between two neighboring cells, from both sides.
+    /// Returns false and leaves the maze alone if they aren't neighbors.
+    fn link(&self, cell: &ICellStrong, other: &ICellStrong) -> bool {
+        if !cells::is_neighbor(cell, other) {
+            return false;
+        }
+        cells::link(cell, other);
+        true
+    }
+
+    /// Puts the wall back between two cells. Returns whether there was a passage to remove.
+    fn unlink(&self, cell: &ICellStrong, other: &ICellStrong) -> bool {
+        if !self.is_linked(cell, other) {
+            return false;
+        }
+        cells::unlink(cell, other);
+        true
+    }
+
+    fn is_linked(&self, cell: &ICellStrong, other: &ICellStrong) -> bool {
+        cells::is_linked(cell, other)
+    }
+
+    /// Removes dead ends, adding loops to the maze. Each dead end is joined to another neighbor with probability `p`,
+    /// so 0.0 leaves the maze as it is and 1.0 removes every dead end.
+    fn braid(&self, p: f64, rng: &dyn RngWrapper<Shuffle=ICellStrong>) {
+        braid::braid(&self.each_cell(), p, rng);
+    }
 }
 
 #[derive(Clone)]
@@ -37,5 +100,6 @@ pub enum GridType {
     StandardGrid,
     PolarGrid,
     HexGrid,
-    TriangleGrid
+    TriangleGrid,
+    WeaveGrid
 }
\ No newline at end of file
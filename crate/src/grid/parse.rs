@@ -0,0 +1,214 @@
+//! Reads mazes drawn in the text format `GridBase::to_string` prints:
+//!
+//! ```text
+//! +---+---+---+
+//! |   |       |
+//! +   +   +---+
+//! |         X |
+//! +---+---+---+
+//! ```
+//!
+//! Cell rows alternate with wall rows. Each cell is four characters wide: `|` or a space for the wall on its
+//! left, then three characters of interior. Wall rows have `+` (or a space) at the corners and `---` or three
+//! spaces under each cell. A space where a wall could be is a passage.
+//!
+//! Any text inside a cell is ignored, except an `X`, which marks the cell as masked out, as in `mask.txt`.
+//! Gaps in the outer wall, often drawn as an entrance and exit, are allowed and ignored.
+//! Trailing spaces may be left off, and blank lines before and after the maze are skipped.
+
+use super::{Grid, mask::Mask, masked_grid::MaskedGrid, standard_grid::StandardGrid};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// 1-based, counting every line of the input.
+    pub line: usize,
+    /// 1-based, in characters.
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// Reads a maze with no masked-out cells.
+pub fn parse_standard(text: &str) -> Result<StandardGrid, ParseError> {
+    let layout = Layout::read(text)?;
+    if let Some((i, j)) = layout.first_void() {
+        return Err(layout.error_at(2 * i + 1, 4 * j + 1, "masked-out cell in a standard grid"));
+    }
+
+    let grid = StandardGrid::new(layout.rows, layout.columns);
+    layout.carve(&grid);
+    Ok(grid)
+}
+
+/// Reads a maze where cells marked with an `X` are masked out.
+pub fn parse_masked(text: &str) -> Result<MaskedGrid, ParseError> {
+    let layout = Layout::read(text)?;
+    let mut mask = Mask::new(layout.rows, layout.columns);
+    for i in 0..layout.rows {
+        for j in 0..layout.columns {
+            mask.set(i, j, !layout.is_void(i, j));
+        }
+    }
+
+    let grid = MaskedGrid::new(mask);
+    layout.carve(&grid);
+    Ok(grid)
+}
+
+/// The maze's characters, checked and sized up but not yet turned into a grid.
+struct Layout {
+    lines: Vec<Vec<char>>,
+    // Line number of `lines[0]` in the input, 0-based.
+    first_line: usize,
+    rows: usize,
+    columns: usize,
+}
+
+impl Layout {
+    fn read(text: &str) -> Result<Layout, ParseError> {
+        // `to_string` starts its output with a carriage return.
+        let text = text.trim_start_matches('\r');
+        let all: Vec<&str> = text.lines().collect();
+        let first_line = all.iter().position(|l| !l.trim().is_empty()).unwrap_or(0);
+        let last_line = all.iter().rposition(|l| !l.trim().is_empty()).map_or(0, |l| l + 1);
+        let lines: Vec<Vec<char>> = all.get(first_line..last_line).unwrap_or(&[]).iter()
+            .map(|l| l.trim_end_matches('\r').chars().collect())
+            .collect();
+
+        let empty = |message: &str| ParseError { line: first_line + 1, column: 1, message: String::from(message) };
+        if !lines.is_empty() && lines.len() % 2 == 0 {
+            return Err(ParseError { line: first_line + lines.len() + 1, column: 1, message: String::from("missing the bottom wall") });
+        }
+        if lines.len() < 3 {
+            return Err(empty("a maze needs at least one row of cells between two walls"));
+        }
+
+        let (widest, width) = lines.iter().map(|l| l.len()).enumerate().max_by_key(|(_, len)| *len).unwrap();
+        if width < 5 {
+            return Err(empty("a maze needs at least one column of cells"));
+        }
+        if (width - 1) % 4 != 0 {
+            let message = String::from("text past the maze's right-hand wall");
+            return Err(ParseError { line: first_line + widest + 1, column: (width - 1) / 4 * 4 + 2, message });
+        }
+
+        let layout = Layout {
+            first_line,
+            rows: lines.len() / 2,
+            columns: (width - 1) / 4,
+            lines,
+        };
+        layout.check()?;
+        Ok(layout)
+    }
+
+    /// Makes sure every wall and corner is one of the characters allowed there.
+    fn check(&self) -> Result<(), ParseError> {
+        for l in 0..self.lines.len() {
+            let last = 4 * self.columns;
+            for c in 0..=last {
+                let ch = self.at(l, c);
+                let expected = match (l % 2 == 0, c % 4 == 0) {
+                    (true, true) => "+ ",
+                    (true, false) => "- ",
+                    (false, true) => "| ",
+                    (false, false) => continue
+                };
+
+                if !expected.contains(ch) {
+                    let message = format!("expected {} but found '{}'", describe(expected), ch);
+                    return Err(self.error_at(l, c, &message));
+                }
+            }
+
+            // A wall under a cell is either all there or not at all.
+            if l % 2 == 0 {
+                for j in 0..self.columns {
+                    let segment: String = (1..4).map(|c| self.at(l, 4 * j + c)).collect();
+                    if segment != "---" && segment != "   " {
+                        return Err(self.error_at(l, 4 * j + 1, &format!("expected \"---\" or three spaces but found \"{}\"", segment)));
+                    }
+                }
+            }
+        }
+
+        for i in 0..self.rows {
+            for j in 0..self.columns {
+                if self.is_void(i, j) {
+                    continue;
+                }
+
+                let east = j + 1 < self.columns && self.is_east_open(i, j);
+                let south = i + 1 < self.rows && self.is_south_open(i, j);
+                if east && self.is_void(i, j + 1) {
+                    return Err(self.error_at(2 * i + 1, 4 * j + 4, "passage into a masked-out cell"));
+                }
+                if south && self.is_void(i + 1, j) {
+                    return Err(self.error_at(2 * i + 2, 4 * j + 1, "passage into a masked-out cell"));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn carve(&self, grid: &dyn Grid) {
+        let cell = |i: usize, j: usize| grid.get_cell(i, j);
+        for i in 0..self.rows {
+            for j in 0..self.columns {
+                if let Some(here) = cell(i, j) {
+                    if j + 1 < self.columns && self.is_east_open(i, j) {
+                        if let Some(east) = cell(i, j + 1) {
+                            grid.link(&here, &east);
+                        }
+                    }
+                    if i + 1 < self.rows && self.is_south_open(i, j) {
+                        if let Some(south) = cell(i + 1, j) {
+                            grid.link(&here, &south);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// The character at (line, column) within the maze, counting missing trailing characters as spaces.
+    fn at(&self, line: usize, column: usize) -> char {
+        self.lines[line].get(column).cloned().unwrap_or(' ')
+    }
+
+    fn is_void(&self, i: usize, j: usize) -> bool {
+        (1..4).any(|c| self.at(2 * i + 1, 4 * j + c) == 'X')
+    }
+
+    fn first_void(&self) -> Option<(usize, usize)> {
+        (0..self.rows)
+            .flat_map(|i| (0..self.columns).map(move |j| (i, j)))
+            .find(|(i, j)| self.is_void(*i, *j))
+    }
+
+    fn is_east_open(&self, i: usize, j: usize) -> bool {
+        self.at(2 * i + 1, 4 * j + 4) == ' '
+    }
+
+    fn is_south_open(&self, i: usize, j: usize) -> bool {
+        self.at(2 * i + 2, 4 * j + 1) == ' '
+    }
+
+    fn error_at(&self, line: usize, column: usize, message: &str) -> ParseError {
+        ParseError { line: self.first_line + line + 1, column: column + 1, message: String::from(message) }
+    }
+}
+
+fn describe(allowed: &str) -> String {
+    let names: Vec<String> = allowed.chars()
+        .map(|c| if c == ' ' { String::from("a space") } else { format!("'{}'", c) })
+        .collect();
+    names.join(" or ")
+}
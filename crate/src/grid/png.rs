@@ -0,0 +1,191 @@
+//! A small PNG encoder for `Raster`, so images can be written without any extra crates.
+//!
+//! Images are always 8-bit RGBA with no filtering. The pixel data is compressed with deflate's fixed Huffman
+//! codes and a simple LZ77 search, which is far from optimal but handles the long runs of wall and
+//! background that make up a maze well.
+
+const SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+/// Encodes `pixels`, four bytes per pixel row by row from the top left, as a PNG file.
+pub fn encode(width: usize, height: usize, pixels: &[u8]) -> Vec<u8> {
+    assert_eq!(pixels.len(), width * height * 4, "expected {} by {} RGBA pixels", width, height);
+
+    let mut header = Vec::with_capacity(13);
+    header.extend(&(width as u32).to_be_bytes());
+    header.extend(&(height as u32).to_be_bytes());
+    // Bit depth 8, color type 6 (RGBA), then the default compression, filter and interlace methods.
+    header.extend(&[8, 6, 0, 0, 0]);
+
+    // Each row starts with its filter type, 0 for none.
+    let mut raw = Vec::with_capacity(height * (width * 4 + 1));
+    for row in pixels.chunks(width * 4).take(height) {
+        raw.push(0);
+        raw.extend(row);
+    }
+
+    let mut png = SIGNATURE.to_vec();
+    chunk(&mut png, b"IHDR", &header);
+    chunk(&mut png, b"IDAT", &zlib(&raw));
+    chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc32(&png[start..]);
+    png.extend(&crc.to_be_bytes());
+}
+
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+pub fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    // 5552 is the most bytes that can be summed before `b` could overflow.
+    for block in data.chunks(5552) {
+        for byte in block {
+            a += *byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+fn zlib(data: &[u8]) -> Vec<u8> {
+    // Deflate with a 32K window, at the default compression level.
+    let mut out = vec![0x78, 0x9C];
+    out.extend(deflate(data));
+    out.extend(&adler32(data).to_be_bytes());
+    out
+}
+
+const WINDOW: usize = 32768;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const HASH_BITS: usize = 15;
+
+const LENGTH_BASE: [usize; 29] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA: [u32; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DISTANCE_BASE: [usize; 30] = [1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577];
+const DISTANCE_EXTRA: [u32; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+
+/// One deflate block using the fixed Huffman codes. Each position is checked against the last place the same
+/// three bytes were seen, and the match is taken if there is one.
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut out = BitWriter::new();
+    // Last block, fixed codes.
+    out.bits(1, 1);
+    out.bits(1, 2);
+
+    let hash = |i: usize| {
+        let key = (data[i] as usize) << 16 | (data[i + 1] as usize) << 8 | data[i + 2] as usize;
+        (key.wrapping_mul(2_654_435_761) >> 7) & ((1 << HASH_BITS) - 1)
+    };
+    let mut last_seen: Vec<Option<usize>> = vec![None; 1 << HASH_BITS];
+
+    let mut i = 0;
+    while i < data.len() {
+        let mut length = 0;
+        let mut distance = 0;
+
+        if i + MIN_MATCH <= data.len() {
+            let h = hash(i);
+            if let Some(candidate) = last_seen[h] {
+                if i - candidate <= WINDOW {
+                    let longest = MAX_MATCH.min(data.len() - i);
+                    length = (0..longest).take_while(|k| data[candidate + k] == data[i + k]).count();
+                    distance = i - candidate;
+                }
+            }
+            last_seen[h] = Some(i);
+        }
+
+        if length >= MIN_MATCH {
+            out.length(length);
+            out.distance(distance);
+            for k in i + 1..i + length {
+                if k + MIN_MATCH <= data.len() {
+                    last_seen[hash(k)] = Some(k);
+                }
+            }
+            i += length;
+        } else {
+            out.literal(data[i] as u32);
+            i += 1;
+        }
+    }
+
+    out.literal(256);
+    out.finish()
+}
+
+/// Packs bits into bytes starting from the least significant bit, as deflate expects.
+struct BitWriter {
+    bytes: Vec<u8>,
+    current: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    fn new() -> BitWriter {
+        BitWriter { bytes: Vec::new(), current: 0, count: 0 }
+    }
+
+    fn bits(&mut self, value: u32, count: u32) {
+        self.current |= value << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.bytes.push(self.current as u8);
+            self.current >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Huffman codes are written most significant bit first, unlike everything else.
+    fn code(&mut self, code: u32, length: u32) {
+        let reversed = (0..length).fold(0, |r, bit| (r << 1) | ((code >> bit) & 1));
+        self.bits(reversed, length);
+    }
+
+    /// A literal byte, or 256 to end the block, in the fixed literal/length code.
+    fn literal(&mut self, symbol: u32) {
+        match symbol {
+            0..=143 => self.code(0x30 + symbol, 8),
+            144..=255 => self.code(0x190 + symbol - 144, 9),
+            256..=279 => self.code(symbol - 256, 7),
+            _ => self.code(0xC0 + symbol - 280, 8),
+        }
+    }
+
+    fn length(&mut self, length: usize) {
+        let index = LENGTH_BASE.iter().rposition(|base| *base <= length).unwrap();
+        self.literal(257 + index as u32);
+        self.bits((length - LENGTH_BASE[index]) as u32, LENGTH_EXTRA[index]);
+    }
+
+    fn distance(&mut self, distance: usize) {
+        let index = DISTANCE_BASE.iter().rposition(|base| *base <= distance).unwrap();
+        self.code(index as u32, 5);
+        self.bits((distance - DISTANCE_BASE[index]) as u32, DISTANCE_EXTRA[index]);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.current as u8);
+        }
+        self.bytes
+    }
+}
@@ -1,9 +1,8 @@
 use std::rc::Rc;
 use std::f64::consts::PI;
-use wasm_bindgen::prelude::JsValue;
 use math::round;
-use crate::grid::{Grid, CellFormatter, canvas::{setup_grid_canvas, remove_old_canvas, draw_line, DrawMode, set_canvas_size}};
-use crate::cells::{ICellStrong, polar_cell::{PolarCellLinkStrong, PolarCell}};
+use crate::grid::{Grid, CellFormatter, backend::Backend, text::{TextCanvas, centered}, canvas::{CanvasBackend, DrawMode}};
+use crate::cells::{ICellStrong, polar_cell::{PolarCellLinkStrong, PolarCellLinkWeak, PolarCell}};
 use crate::rng::RngWrapper;
 
 pub static POLAR_GRID: &str = "polar_grid";
@@ -165,100 +164,106 @@ impl Grid for PolarGrid {
         None
     }
 
-    fn to_string(&self, _contents: &dyn CellFormatter) -> String {
-        // Not sure how to make this a text string...
-        "".to_string()
+    /// Unrolls the rings into rows, from the center out, with each cell as wide as its share of the circle:
+    ///
+    /// ```text
+    /// +-------------------------------------------------------+
+    /// |                           0                           |
+    /// +-------+-------+-------+       +-------+-------+       +
+    /// |   7   |   3       2       1   |   6   |   2       1   |
+    /// +   +   +---+   +---+---+---+---+   +   +---+   +---+---+
+    ///   8 | 6   5   4   5 | a   9   8   7 | 5   4   3 | a   9
+    /// +---+---+---+---+---+---+---+---+---+---+---+---+---+---+
+    /// ```
+    ///
+    /// Each ring wraps around, so the walls at the far left and far right are the same wall.
+    fn to_string(&self, contents: &dyn CellFormatter) -> String {
+        let widest = self.cells.iter().map(|ring| ring.len()).max().unwrap_or(0);
+        let width = 4 * widest;
+        let mut text = TextCanvas::new(width + 1, 2 * self.cells.len() + 1);
+        text.put(0, 0, "+");
+
+        for (r, ring) in self.cells.iter().enumerate() {
+            let cell_width = width / ring.len();
+            let is_open = |other: &Option<PolarCellLinkWeak>, c: &PolarCell| {
+                other.as_ref().and_then(|o| o.upgrade()).map_or(false, |o| c.is_linked(o))
+            };
+
+            for cell in ring.iter() {
+                if let Some(cell) = cell {
+                    let c = cell.borrow();
+                    let x = c.column * cell_width;
+
+                    let inward = if is_open(&c.inward, &c) { " " } else { "-" };
+                    text.put(2 * r, x, &format!("+{}", inward.repeat(cell_width - 1)));
+                    if r + 1 == self.cells.len() {
+                        text.put(2 * r + 2, x, &format!("+{}", "-".repeat(cell_width - 1)));
+                    }
+
+                    let ics: ICellStrong = Rc::clone(cell) as ICellStrong;
+                    text.put(2 * r + 1, x + 1, &centered(&contents.contents_of(&ics), cell_width - 1));
+                    text.put(2 * r + 1, x + cell_width, if is_open(&c.cw, &c) { " " } else { "|" });
+                    if c.column == 0 {
+                        text.put(2 * r + 1, 0, if is_open(&c.ccw, &c) { " " } else { "|" });
+                    }
+                }
+            }
+            text.put(2 * r, width, "+");
+        }
+        text.put(2 * self.cells.len(), width, "+");
+
+        text.finish()
     }
 
     fn size(&self) -> usize {
         self.cells.iter().fold(0, |acc, r| acc + r.len())
     }
 
-    fn braid(&self, p: f64, rng: &dyn RngWrapper<Shuffle=ICellStrong>) {
-        // TODO: not yet implemented
-    }
-
     fn to_web(&self, formatter: &dyn CellFormatter, colorize: bool) {
-        let size = 20;
-        let img_size = 2 * self.rows * size;
-
-        remove_old_canvas(POLAR_GRID);
-        let context = setup_grid_canvas(POLAR_GRID).unwrap();
-        set_canvas_size(POLAR_GRID, img_size, img_size);
-        
-        context.set_fill_style(&JsValue::from_str("black"));
-        context.set_stroke_style(&JsValue::from_str("black"));
+        self.draw(&mut CanvasBackend::new(POLAR_GRID), formatter, colorize, self.cell_size());
+    }
 
-        let center = img_size / 2;
+    fn draw(&self, backend: &mut dyn Backend, formatter: &dyn CellFormatter, colorize: bool, size: f64) {
+        let img_size = 2.0 * self.rows as f64 * size;
+        let center = img_size / 2.0;
+        backend.begin(img_size, img_size);
 
         for mode in [DrawMode::Background, DrawMode::Line].iter() {
             for cell in self.each_polar_cell().iter() {
                 if let Some(cell) = cell {
                     let c = cell.as_ref().borrow();
-                    
 
                     let theta = 2.0 * PI / self.cells[c.row].len() as f64;
-                    let inner_radius = (c.row * size) as f64;
-                    let outer_radius = ((c.row + 1) * size) as f64;
+                    let inner_radius = c.row as f64 * size;
+                    let outer_radius = (c.row + 1) as f64 * size;
                     let theta_ccw = c.column as f64 * theta;
                     let theta_cw = (c.column + 1) as f64 * theta;
 
-                    // let ax = (center as f64 + (inner_radius * theta_ccw.cos()) ) as f64;
-                    // let ay = (center as f64 + (inner_radius * theta_ccw.sin()) ) as f64;
-                    // let bx = (center as f64 + (outer_radius * theta_ccw.cos()) ) as f64;
-                    // let by = (center as f64 + (outer_radius * theta_ccw.sin()) ) as f64;
-                    let cx = (center as f64 + (inner_radius * theta_cw.cos()) ) as f64;
-                    let cy = (center as f64 + (inner_radius * theta_cw.sin()) ) as f64;
-                    let dx = (center as f64 + (outer_radius * theta_cw.cos()) ) as f64;
-                    let dy = (center as f64 + (outer_radius * theta_cw.sin()) ) as f64;
-
-                    // web_sys::console::log_1(&JsValue::from_str(
-                    //     &format!("cr: {} {}, t: {}, a: {} {}, b: {} {}, c: {} {}, d: {} {}", cell.row, cell.column, theta, ax, ay, bx, by, cx, cy, dx, dy)));
-
                     match mode {
                         DrawMode::Background => {
                             if colorize {
-                                // let points = vec![(ax, ay), (bx, by), (cx, cy), (dx, dy)];
-                                let ics: ICellStrong =  Rc::clone(cell) as ICellStrong;
-                                let color = formatter.background_color(&ics).to_string();
- 
-                                context.set_fill_style(&JsValue::from_str(&color));
-                                context.set_stroke_style(&JsValue::from_str(&color));
-                                
-                                context.begin_path();
-                                // arc(x, y, radius, startAngle, endAngle, anticlockwise)
-                                context.arc(center as f64, center as f64, inner_radius, theta_ccw, theta_cw).unwrap();
-                                context.arc_with_anticlockwise(center as f64, center as f64, outer_radius, theta_cw, theta_ccw, true).unwrap();
-
-                                context.fill();
-                                context.stroke();
-                                context.set_fill_style(&JsValue::from_str("black"));
-                                context.set_stroke_style(&JsValue::from_str("black"));
+                                let ics: ICellStrong = Rc::clone(cell) as ICellStrong;
+                                backend.sector(center, center, inner_radius, outer_radius, theta_ccw, theta_cw, &formatter.background_color(&ics));
                             }
                         },
                         DrawMode::Line => {
                             if c.row == 0 {
                                 // Hide the line in the middle. Not really needed but makes it a little prettier.
-                                continue; 
+                                continue;
+                            }
+                            if c.cw.is_none() || !c.is_linked(c.cw.as_ref().unwrap().upgrade().unwrap()) {
+                                backend.line(center + inner_radius * theta_cw.cos(), center + inner_radius * theta_cw.sin(),
+                                    center + outer_radius * theta_cw.cos(), center + outer_radius * theta_cw.sin());
                             }
-                            if c.cw.is_none() || (c.cw.is_some() && !c.is_linked(c.cw.as_ref().unwrap().upgrade().unwrap().clone())) {
-                                draw_line(&context, cx, cy, dx, dy);
+                            if c.inward.is_none() || !c.is_linked(c.inward.as_ref().unwrap().upgrade().unwrap()) {
+                                backend.arc(center, center, inner_radius, theta_ccw, theta_cw);
                             }
-                            
-                            if c.inward.is_none() || (c.inward.is_some() && !c.is_linked(c.inward.as_ref().unwrap().upgrade().unwrap().clone())) {
-                                context.begin_path();
-                                context.arc(center as f64, center as f64, inner_radius, theta_ccw, theta_cw).unwrap();
-                                context.stroke();
-                            }                                     
                         }
-                    }                
-                    
+                    }
                 }
             }
         }
-        
-        context.begin_path();
-        context.arc(center as f64, center as f64, (self.rows() * size) as f64, 0.0, PI * 2.0).unwrap();
-        context.stroke();
+
+        backend.arc(center, center, self.rows as f64 * size, 0.0, 2.0 * PI);
     }
 }
\ No newline at end of file
@@ -0,0 +1,192 @@
+use crate::grid::{png, backend::Backend};
+
+pub type Rgba = [u8; 4];
+
+pub const BLACK: Rgba = [0, 0, 0, 255];
+pub const WHITE: Rgba = [255, 255, 255, 255];
+
+/// How `Grid::to_raster` draws a maze.
+#[derive(Debug, Clone)]
+pub struct RasterOptions {
+    /// The width of a cell in pixels, or of a ring on a polar grid.
+    pub cell_size: f64,
+    pub wall_width: f64,
+    /// Fill cells with the formatter's `background_color`, e.g. to shade by distance with a `DistanceGrid`.
+    pub colorize: bool,
+    pub background: Rgba,
+}
+
+impl Default for RasterOptions {
+    fn default() -> RasterOptions {
+        RasterOptions {
+            cell_size: 20.0,
+            wall_width: 2.0,
+            colorize: true,
+            background: WHITE,
+        }
+    }
+}
+
+/// An RGBA image that mazes are drawn onto without a browser, for thumbnails and batch jobs.
+/// Shapes are filled wherever they cover the middle of a pixel, with no anti-aliasing,
+/// so the same maze always comes out the same.
+#[derive(Debug, Clone)]
+pub struct Raster {
+    pub width: usize,
+    pub height: usize,
+    /// Four bytes per pixel, row by row from the top left.
+    pub pixels: Vec<u8>,
+    wall_width: f64,
+    background: Rgba,
+    // Shapes are shifted by this much so walls on the edge of the maze aren't clipped.
+    margin: f64,
+}
+
+impl Raster {
+    /// An empty image, sized when something is drawn on it.
+    pub fn new(options: &RasterOptions) -> Raster {
+        Raster {
+            width: 0,
+            height: 0,
+            pixels: Vec::new(),
+            wall_width: options.wall_width,
+            background: options.background,
+            margin: (options.wall_width / 2.0).ceil() + 1.0,
+        }
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Rgba {
+        let i = (y * self.width + x) * 4;
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2], self.pixels[i + 3]]
+    }
+
+    pub fn to_png(&self) -> Vec<u8> {
+        png::encode(self.width, self.height, &self.pixels)
+    }
+
+    /// Scanline fill: each row of pixels is filled between pairs of polygon edges crossing its middle.
+    fn fill(&mut self, points: &[(f64, f64)], color: Rgba) {
+        if points.len() < 3 {
+            return;
+        }
+
+        let points: Vec<(f64, f64)> = points.iter().map(|(x, y)| (x + self.margin, y + self.margin)).collect();
+        let top = points.iter().map(|p| p.1).fold(std::f64::INFINITY, f64::min).max(0.0) as usize;
+        let bottom = (points.iter().map(|p| p.1).fold(std::f64::NEG_INFINITY, f64::max).ceil().max(0.0) as usize).min(self.height);
+
+        for y in top..bottom {
+            let middle = y as f64 + 0.5;
+            let mut crossings: Vec<f64> = Vec::new();
+            for (i, a) in points.iter().enumerate() {
+                let b = points[(i + 1) % points.len()];
+                if (a.1 <= middle) != (b.1 <= middle) {
+                    crossings.push(a.0 + (middle - a.1) / (b.1 - a.1) * (b.0 - a.0));
+                }
+            }
+            crossings.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+            for span in crossings.chunks(2).filter(|s| s.len() == 2) {
+                // Pixels whose middle lies in [start, end).
+                let start = (span[0] - 0.5).ceil().max(0.0) as usize;
+                let end = ((span[1] - 0.5).ceil().max(0.0) as usize).min(self.width);
+                for x in start..end {
+                    let i = (y * self.width + x) * 4;
+                    self.pixels[i..i + 4].copy_from_slice(&color);
+                }
+            }
+        }
+    }
+}
+
+impl Backend for Raster {
+    /// Makes the image big enough for a drawing `width` by `height` pixels, plus room for the outer walls.
+    fn begin(&mut self, width: f64, height: f64) {
+        self.width = (width + 2.0 * self.margin).ceil() as usize;
+        self.height = (height + 2.0 * self.margin).ceil() as usize;
+        self.pixels = self.background.iter().cloned().cycle().take(self.width * self.height * 4).collect();
+    }
+
+    /// Colors it can't read are left unfilled, like the empty string.
+    fn shape(&mut self, points: &[(f64, f64)], color: &str) {
+        if let Some(color) = parse_color(color) {
+            self.fill(points, color);
+        }
+    }
+
+    fn sector(&mut self, cx: f64, cy: f64, inner: f64, outer: f64, from: f64, to: f64, color: &str) {
+        if let Some(color) = parse_color(color) {
+            let mut points = arc_points(cx, cy, outer, from, to);
+            let mut inside = arc_points(cx, cy, inner, from, to);
+            inside.reverse();
+            points.extend(inside);
+            self.fill(&points, color);
+        }
+    }
+
+    /// Draws a wall with square ends, so that walls meeting at a corner join up.
+    fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64) {
+        let half = self.wall_width / 2.0;
+        let (dx, dy) = (x2 - x1, y2 - y1);
+        let length = (dx * dx + dy * dy).sqrt();
+        if length == 0.0 {
+            return;
+        }
+
+        // Along and across the line, each half a wall wide.
+        let (ax, ay) = (dx / length * half, dy / length * half);
+        let (cx, cy) = (-ay, ax);
+        let points = [
+            (x1 - ax + cx, y1 - ay + cy),
+            (x2 + ax + cx, y2 + ay + cy),
+            (x2 + ax - cx, y2 + ay - cy),
+            (x1 - ax - cx, y1 - ay - cy),
+        ];
+        self.fill(&points, BLACK);
+    }
+
+    fn arc(&mut self, cx: f64, cy: f64, r: f64, from: f64, to: f64) {
+        let points = arc_points(cx, cy, r, from, to);
+        for pair in points.windows(2) {
+            self.line(pair[0].0, pair[0].1, pair[1].0, pair[1].1);
+        }
+    }
+}
+
+/// Points along an arc, close enough together that the straight lines between them look round.
+fn arc_points(cx: f64, cy: f64, r: f64, from: f64, to: f64) -> Vec<(f64, f64)> {
+    let steps = ((to - from) * r / 2.0).ceil().max(1.0) as usize;
+    (0..=steps)
+        .map(|i| from + (to - from) * i as f64 / steps as f64)
+        .map(|theta| (cx + r * theta.cos(), cy + r * theta.sin()))
+        .collect()
+}
+
+/// Reads `rgb(r, g, b)`, `#rrggbb`, `black` and `white`, which covers the colors the formatters produce.
+pub fn parse_color(color: &str) -> Option<Rgba> {
+    let color = color.trim();
+
+    if color.starts_with("rgb(") && color.ends_with(')') {
+        let parts: Vec<u8> = color[4..color.len() - 1].split(',')
+            .filter_map(|p| p.trim().parse::<f64>().ok())
+            .map(|p| p.max(0.0).min(255.0) as u8)
+            .collect();
+        return match parts.as_slice() {
+            [r, g, b] => Some([*r, *g, *b, 255]),
+            _ => None
+        };
+    }
+
+    if color.starts_with('#') && color.len() == 7 {
+        let channel = |i: usize| u8::from_str_radix(&color[i..i + 2], 16).ok();
+        return match (channel(1), channel(3), channel(5)) {
+            (Some(r), Some(g), Some(b)) => Some([r, g, b, 255]),
+            _ => None
+        };
+    }
+
+    match color {
+        "black" => Some(BLACK),
+        "white" => Some(WHITE),
+        _ => None
+    }
+}
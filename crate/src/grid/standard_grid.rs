@@ -1,5 +1,4 @@
-use std::rc::{Rc};
-use super::{grid_base::GridBase, Grid, CellFormatter};
+use super::{grid_base::{GridBase, Sparsity}, Grid, CellFormatter, backend::Backend, canvas::CanvasBackend};
 use crate::cells::{ICellStrong, cell::{Cell, CellLinkStrong}};
 use crate::rng::RngWrapper;
 
@@ -19,6 +18,16 @@ impl StandardGrid {
         std_grid.grid.configure_cells();
         std_grid
     }
+
+    /// Draws the maze with box-drawing characters for a terminal. See `GridBase::to_unicode`.
+    pub fn to_unicode(&self, formatter: &dyn CellFormatter, colorize: bool) -> String {
+        self.grid.to_unicode(formatter, colorize)
+    }
+
+    /// Culls dead ends, leaving void cells behind. See `GridBase::sparsify`.
+    pub fn sparsify(&mut self, sparsity: Sparsity, rng: &dyn RngWrapper<Shuffle=ICellStrong>) -> Vec<(usize, usize)> {
+        self.grid.sparsify(sparsity, rng)
+    }
 }
 
 impl Grid for StandardGrid {
@@ -38,15 +47,22 @@ impl Grid for StandardGrid {
         }   
     }
 
+    /// Keeps trying until it lands on a cell that hasn't been cut out by `sparsify`.
     fn random_cell(&self, rng: &dyn RngWrapper<Shuffle=ICellStrong>) -> Option<ICellStrong> {
-        let row: usize = rng.gen_range(0, self.grid.rows);
-        let col: usize = rng.gen_range(0, self.grid.columns);
-        self.get_cell(row, col)
+        loop {
+            let row: usize = rng.gen_range(0, self.grid.rows);
+            let col: usize = rng.gen_range(0, self.grid.columns);
+            if let Some(cell) = self.get_cell(row, col) {
+                return Some(cell);
+            }
+            if self.size() == 0 {
+                return None;
+            }
+        }
     }
 
     fn each_cell(&self) -> Vec<Option<ICellStrong>> {
-        self.grid.each_cell().iter()
-            .map(|c| Some(Rc::clone(&c.as_ref().unwrap()) as ICellStrong)).collect()
+        self.grid.each_cell()
     }
 
     fn rows(&self) -> usize {
@@ -62,9 +78,7 @@ impl Grid for StandardGrid {
     }
     
     fn get_cell(&self, row: usize, column: usize) -> Option<ICellStrong> {
-        let cell = self.grid.get_cell(row, column);
-
-        Some(Rc::clone(&cell.unwrap()) as ICellStrong)
+        self.grid.get_cell(row, column)
     }
 
     fn to_string(&self, contents: &dyn CellFormatter) -> String {
@@ -72,14 +86,18 @@ impl Grid for StandardGrid {
     }
 
     fn size(&self) -> usize {
-        self.grid.rows * self.grid.columns
+        self.grid.each_cell().iter().filter(|c| c.is_some()).count()
     }
     
-    fn braid(&self, p: f64, rng: &dyn RngWrapper<Shuffle=ICellStrong>) {
-        self.grid.braid(p, rng);
+    fn to_web(&self, formatter: &dyn CellFormatter, colorize: bool) {
+        self.draw(&mut CanvasBackend::new(STANDARD_GRID), formatter, colorize, self.cell_size());
     }
 
-    fn to_web(&self, formatter: &dyn CellFormatter, colorize: bool) {
-        self.grid.to_web(formatter, colorize);
+    fn draw(&self, backend: &mut dyn Backend, formatter: &dyn CellFormatter, colorize: bool, size: f64) {
+        self.grid.draw(backend, formatter, colorize, size);
+    }
+
+    fn cell_size(&self) -> f64 {
+        15.0
     }
 }
\ No newline at end of file
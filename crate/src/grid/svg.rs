@@ -0,0 +1,94 @@
+use std::f64::consts::PI;
+use std::fmt::Write;
+use super::backend::Backend;
+
+/// Builds an SVG document one shape at a time, for drawing mazes without a browser.
+/// Walls are 1 unit wide.
+#[derive(Default)]
+pub struct Svg {
+    width: f64,
+    height: f64,
+    body: String,
+}
+
+impl Svg {
+    pub fn new() -> Svg {
+        Svg::default()
+    }
+
+    /// The finished document. Like the canvas, it's padded a little so walls on the edge aren't clipped.
+    pub fn finish(self) -> String {
+        format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"-2.5 -2.5 {w} {h}\">\n\
+                 <g stroke=\"black\" stroke-width=\"1\" stroke-linecap=\"square\" fill=\"none\">\n{}</g>\n</svg>\n",
+            self.body, w = num(self.width + 5.0), h = num(self.height + 5.0))
+    }
+
+    fn circle(&mut self, cx: f64, cy: f64, r: f64) {
+        writeln!(self.body, "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\"/>", num(cx), num(cy), num(r)).unwrap();
+    }
+}
+
+impl Backend for Svg {
+    fn begin(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+    }
+
+    fn line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64) {
+        writeln!(self.body, "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>", num(x1), num(y1), num(x2), num(y2)).unwrap();
+    }
+
+    fn shape(&mut self, points: &[(f64, f64)], color: &str) {
+        if points.is_empty() || color.is_empty() {
+            return;
+        }
+
+        let points: Vec<String> = points.iter().map(|(x, y)| format!("{},{}", num(*x), num(*y))).collect();
+        writeln!(self.body, "  <polygon points=\"{}\" fill=\"{}\" stroke=\"{}\"/>", points.join(" "), color, color).unwrap();
+    }
+
+    fn sector(&mut self, cx: f64, cy: f64, inner: f64, outer: f64, from: f64, to: f64, color: &str) {
+        if color.is_empty() {
+            return;
+        }
+
+        if to - from >= 2.0 * PI - 1e-9 {
+            // A whole ring can't be drawn as a single arc, so fill a circle and cut the hole out of it.
+            let hole = if inner > 0.0 { circle_path(cx, cy, inner) } else { String::new() };
+            writeln!(self.body, "  <path d=\"{}{}\" fill=\"{}\" fill-rule=\"evenodd\" stroke=\"{}\"/>",
+                circle_path(cx, cy, outer), hole, color, color).unwrap();
+            return;
+        }
+
+        let large = if to - from > PI { 1 } else { 0 };
+        let at = |r: f64, theta: f64| format!("{} {}", num(cx + r * theta.cos()), num(cy + r * theta.sin()));
+        writeln!(self.body, "  <path d=\"M {} A {} {} 0 {} 1 {} L {} A {} {} 0 {} 0 {} Z\" fill=\"{}\" stroke=\"{}\"/>",
+            at(inner, from), num(inner), num(inner), large, at(inner, to),
+            at(outer, to), num(outer), num(outer), large, at(outer, from),
+            color, color).unwrap();
+    }
+
+    fn arc(&mut self, cx: f64, cy: f64, r: f64, from: f64, to: f64) {
+        if to - from >= 2.0 * PI - 1e-9 {
+            self.circle(cx, cy, r);
+            return;
+        }
+
+        let large = if to - from > PI { 1 } else { 0 };
+        writeln!(self.body, "  <path d=\"M {} {} A {} {} 0 {} 1 {} {}\"/>",
+            num(cx + r * from.cos()), num(cy + r * from.sin()), num(r), num(r), large,
+            num(cx + r * to.cos()), num(cy + r * to.sin())).unwrap();
+    }
+}
+
+fn circle_path(cx: f64, cy: f64, r: f64) -> String {
+    format!("M {} {} A {r} {r} 0 1 1 {} {} A {r} {r} 0 1 1 {} {} Z ",
+        num(cx + r), num(cy), num(cx - r), num(cy), num(cx + r), num(cy), r = num(r))
+}
+
+/// Rounds to two decimal places and drops trailing zeros, to keep the output small.
+fn num(n: f64) -> String {
+    let s = format!("{:.2}", n);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" { String::from("0") } else { String::from(s) }
+}
@@ -0,0 +1,35 @@
+/// A block of characters that text mazes are drawn into by position, for shapes like hexagons
+/// that can't be built up one row of cells at a time.
+pub struct TextCanvas {
+    lines: Vec<Vec<char>>,
+}
+
+impl TextCanvas {
+    pub fn new(width: usize, height: usize) -> TextCanvas {
+        TextCanvas { lines: vec![vec![' '; width]; height] }
+    }
+
+    /// Writes `text` starting at (line, column). Anything past the right-hand edge is dropped.
+    pub fn put(&mut self, line: usize, column: usize, text: &str) {
+        let line = &mut self.lines[line];
+        for (i, ch) in text.chars().enumerate() {
+            if let Some(at) = line.get_mut(column + i) {
+                *at = ch;
+            }
+        }
+    }
+
+    /// The drawing, one line per row, with trailing spaces trimmed.
+    pub fn finish(self) -> String {
+        let lines: Vec<String> = self.lines.iter()
+            .map(|l| l.iter().collect::<String>().trim_end().to_string())
+            .collect();
+        lines.join("\n") + "\n"
+    }
+}
+
+/// Pads or cuts `text` to exactly `width` characters, centered, for showing a cell's contents.
+pub fn centered(text: &str, width: usize) -> String {
+    let text: String = text.chars().take(width).collect();
+    format!("{:^width$}", text, width = width)
+}
@@ -1,6 +1,5 @@
 use std::rc::Rc;
-use wasm_bindgen::prelude::JsValue;
-use super::{Grid, CellFormatter, canvas::{remove_old_canvas, setup_grid_canvas, draw_shape, draw_line, DrawMode, set_canvas_size}};
+use super::{Grid, CellFormatter, backend::Backend, text::{TextCanvas, centered}, canvas::{CanvasBackend, DrawMode}};
 use crate::rng::RngWrapper;
 use crate::cells::{ICellStrong, triangle_cell::{TriangleCellStrong, TriangleCellWeak, TriangleCell}};
 
@@ -71,38 +70,83 @@ impl Grid for TriangleGrid {
         None
     }
 
-    fn to_string(&self, _contents: &dyn CellFormatter) -> String {
-        "".to_string()
+    /// Draws rows of triangles pointing alternately up and down, with the contents of each cell in its middle:
+    ///
+    /// ```text
+    ///     ____  ____
+    ///   /           /
+    ///  /0  1  2  3 /
+    /// /____       /
+    /// \           \
+    ///  \5  4  3  4 \
+    ///   \ ____  ____\
+    /// ```
+    fn to_string(&self, contents: &dyn CellFormatter) -> String {
+        let mut text = TextCanvas::new(3 * self.columns + 3, 3 * self.rows + 1);
+
+        for cell in self.each_triangle_cell().iter() {
+            if let Some(cell) = cell {
+                let c = cell.borrow();
+                let x = 3 * c.column;
+                let line = 3 * c.row + 1;
+
+                // Walls run down a line at a time, from the top of the row to its base.
+                if c.upright() {
+                    for k in 0..3 {
+                        if c.west.is_none() {
+                            text.put(line + k, x + 2 - k, "/");
+                        }
+                        if c.is_not_linked(&c.east) {
+                            text.put(line + k, x + 3 + k, "\\");
+                        }
+                    }
+                    if c.south.is_none() {
+                        text.put(line + 2, x + 1, "____");
+                    }
+                } else {
+                    for k in 0..3 {
+                        if c.west.is_none() {
+                            text.put(line + k, x + k, "\\");
+                        }
+                        if c.is_not_linked(&c.east) {
+                            text.put(line + k, x + 5 - k, "/");
+                        }
+                    }
+                    if c.is_not_linked(&c.north) {
+                        text.put(line - 1, x + 1, "____");
+                    }
+                }
+
+                let ics: ICellStrong = Rc::clone(cell) as ICellStrong;
+                text.put(line + 1, x + 2, &centered(&contents.contents_of(&ics), 2));
+            }
+        }
+
+        text.finish()
     }
 
     fn size(&self) -> usize {
         self.rows * self.columns
     }
 
-    fn braid(&self, p: f64, rng: &dyn RngWrapper<Shuffle=ICellStrong>) {
-        // TODO: not yet implemented
+    fn to_web(&self, formatter: &dyn CellFormatter, colorize: bool) {
+        self.draw(&mut CanvasBackend::new(TRIANGLE_GRID), formatter, colorize, self.cell_size());
     }
 
-    fn to_web(&self, formatter: &dyn CellFormatter, colorize: bool) {
-        let size = 40_f64;
+    fn draw(&self, backend: &mut dyn Backend, formatter: &dyn CellFormatter, colorize: bool, size: f64) {
         let half_width = size / 2_f64;
         let height = size * 3_f64.sqrt() / 2_f64;
         let half_height = height / 2_f64;
-        let img_width = (size * ((self.columns as f64) + 1_f64) / 2_f64).trunc() as usize;
-        let img_height = (height * (self.rows as f64)).trunc() as usize;
-        
-        remove_old_canvas(TRIANGLE_GRID);
-        let context = setup_grid_canvas(TRIANGLE_GRID).unwrap();
-        context.set_fill_style(&JsValue::from_str("black"));
-        context.set_stroke_style(&JsValue::from_str("black"));
-        set_canvas_size(TRIANGLE_GRID, img_width, img_height);
+        let img_width = (size * ((self.columns as f64) + 1_f64) / 2_f64).trunc();
+        let img_height = (height * (self.rows as f64)).trunc();
+        backend.begin(img_width, img_height);
 
         for mode in [DrawMode::Background, DrawMode::Line].iter() {
             for cell in self.each_triangle_cell().iter() {
                 if let Some(cell) = cell {
                     let cx = half_width + (cell.borrow().column as f64) * half_width;
                     let cy = half_height + (cell.borrow().row as f64) * height;
-                    
+
                     let west_x = (cx - half_width).trunc();
                     let mid_x = cx.trunc();
                     let east_x = (cx + half_width).trunc();
@@ -116,33 +160,34 @@ impl Grid for TriangleGrid {
                     match mode {
                         DrawMode::Background => {
                             if colorize {
-                                let points = vec![(west_x, base_y), (mid_x, apex_y), (east_x, base_y)];
-                                let ics: ICellStrong =  Rc::clone(cell) as ICellStrong;
-                                let color = formatter.background_color(&ics);
-                                draw_shape(&context, points, &color);
+                                let ics: ICellStrong = Rc::clone(cell) as ICellStrong;
+                                backend.shape(&[(west_x, base_y), (mid_x, apex_y), (east_x, base_y)], &formatter.background_color(&ics));
                             }
                         },
                         DrawMode::Line => {
-                            if cell.borrow().west.is_none() {
-                                draw_line(&context, west_x, base_y, mid_x, apex_y);
+                            let c = cell.borrow();
+                            if c.west.is_none() {
+                                backend.line(west_x, base_y, mid_x, apex_y);
                             }
-
-                            if cell.borrow().is_not_linked(&cell.borrow().east){
-                                draw_line(&context, east_x, base_y, mid_x, apex_y);
+                            if c.is_not_linked(&c.east) {
+                                backend.line(east_x, base_y, mid_x, apex_y);
                             }
 
-                            let no_south = cell.borrow().upright() && cell.borrow().south.is_none();
-                            let not_linked = !cell.borrow().upright() && cell.borrow().is_not_linked(&cell.borrow().north);
-
+                            let no_south = c.upright() && c.south.is_none();
+                            let not_linked = !c.upright() && c.is_not_linked(&c.north);
                             if no_south || not_linked {
-                                draw_line(&context, east_x, base_y, west_x, base_y);
-                            }                    
+                                backend.line(east_x, base_y, west_x, base_y);
+                            }
                         }
-                    }                    
+                    }
                 }
             }
         }
     }
+
+    fn cell_size(&self) -> f64 {
+        40.0
+    }
 }
 
 fn is_not_linked(cell: &TriangleCellStrong, other: &Option<TriangleCellWeak>) -> bool {
@@ -0,0 +1,232 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+use super::{Grid, CellFormatter, text::centered, backend::Backend, grid_base::{inset_cell_lines, Passages}, canvas::{CanvasBackend, DrawMode}};
+use crate::rng::RngWrapper;
+use crate::cells::{ICellStrong, weave_cell::{WeaveCellStrong, WeaveCell}};
+
+pub static WEAVE_GRID: &str = "weave_grid";
+
+/// A standard grid where passages may tunnel under perpendicular corridors.
+/// Under cells are only created while the maze is carved, and are reported after the grid's own cells.
+/// Their `row()` is offset by `rows` so they can be told apart from the cell they run under.
+pub struct WeaveGrid {
+    pub cells: Vec<Vec<Option<WeaveCellStrong>>>,
+    pub under_cells: Rc<RefCell<Vec<WeaveCellStrong>>>,
+    pub rows: usize,
+    pub columns: usize,
+    _cells: Option<Vec<Vec<Option<ICellStrong>>>>
+}
+
+impl Grid for WeaveGrid {
+    fn new_cell(&self, row: usize, column: usize) -> ICellStrong {
+        WeaveCell::new(row, column, self.rows, Rc::downgrade(&self.under_cells)) as ICellStrong
+    }
+
+    fn prepare_grid(&mut self) {
+        for i in 0..self.rows {
+            let mut row: Vec<Option<WeaveCellStrong>> = Vec::new();
+
+            for j in 0..self.columns {
+                row.push(Some(WeaveCell::new(i, j, self.rows, Rc::downgrade(&self.under_cells))));
+            }
+            self.cells.push(row);
+        }
+    }
+
+    fn random_cell(&self, rng: &dyn RngWrapper<Shuffle=ICellStrong>) -> Option<ICellStrong> {
+        let row: usize = rng.gen_range(0, self.rows);
+        let col: usize = rng.gen_range(0, self.columns);
+        self.get_cell(row, col)
+    }
+
+    fn each_cell(&self) -> Vec<Option<ICellStrong>> {
+        self.each_weave_cell().iter()
+            .map(|x| {
+                if let Some(x) = x {
+                    Some(Rc::clone(x) as ICellStrong)
+                }
+                else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn columns(&self) -> usize {
+        self.columns
+    }
+
+    fn cells(&self) -> &Vec<Vec<Option<ICellStrong>>> {
+        self._cells.as_ref().unwrap()
+    }
+
+    fn get_cell(&self, row: usize, column: usize) -> Option<ICellStrong> {
+        if column >= self.columns {
+            return None;
+        }
+
+        if row >= self.rows {
+            return self.under_cells.borrow().iter()
+                .find(|c| c.borrow().row + self.rows == row && c.borrow().column == column)
+                .map(|c| Rc::clone(c) as ICellStrong);
+        }
+
+        if let Some(cell) = self.cells[row][column].clone() {
+            return Some(Rc::clone(&cell) as ICellStrong);
+        }
+        None
+    }
+
+    /// Draws the grid with the same `+---+` characters as `GridBase::to_string`. A wall is open wherever a passage
+    /// goes through it, over or under. Cells show their contents, and a crossing left blank shows which way
+    /// the passage on top runs: ` - ` for east to west, ` | ` for north to south.
+    fn to_string(&self, contents: &dyn CellFormatter) -> String {
+        let mut output = String::from("\r+");
+        output += &"---+".repeat(self.columns);
+        output += "\r\n";
+
+        for row in self.cells.iter() {
+            let mut top = String::from("|");
+            let mut bottom = String::from("+");
+
+            for cell in row.iter().filter_map(|c| c.as_ref()) {
+                let c = cell.borrow();
+                let under = self.under_cells.borrow().iter()
+                    .find(|u| u.borrow().row == c.row && u.borrow().column == c.column)
+                    .map(|u| Rc::clone(u));
+                let under = under.as_ref().map(|u| u.borrow());
+
+                let body = centered(&contents.contents_of(&(Rc::clone(cell) as ICellStrong)), 3);
+                top += match under.as_ref() {
+                    _ if !body.trim().is_empty() => body.as_str(),
+                    Some(u) if u.is_linked_to(&u.north) => " - ",
+                    Some(_) => " | ",
+                    None => "   "
+                };
+                let east = c.is_linked_to(&c.east) || under.as_ref().map_or(false, |u| u.is_linked_to(&u.east));
+                top += if east { " " } else { "|" };
+
+                let south = c.is_linked_to(&c.south) || under.as_ref().map_or(false, |u| u.is_linked_to(&u.south));
+                bottom += if south { "   +" } else { "---+" };
+            }
+
+            output += &format!("{}\r\n", top);
+            output += &format!("{}\r\n", bottom);
+        }
+
+        output
+    }
+
+    /// Counts under cells too, so it always matches `each_cell`.
+    fn size(&self) -> usize {
+        self.rows * self.columns + self.under_cells.borrow().len()
+    }
+
+    fn to_web(&self, formatter: &dyn CellFormatter, colorize: bool) {
+        self.draw(&mut CanvasBackend::new(WEAVE_GRID), formatter, colorize, self.cell_size());
+    }
+
+    fn draw(&self, backend: &mut dyn Backend, formatter: &dyn CellFormatter, colorize: bool, size: f64) {
+        let inset = (size * 0.15_f64).trunc();
+        backend.begin(size * self.columns as f64, size * self.rows as f64);
+
+        for mode in [DrawMode::Background, DrawMode::Line].iter() {
+            for cell in self.each_weave_cell().iter() {
+                if let Some(cell) = cell {
+                    let c = cell.borrow();
+                    let x = (c.column as f64) * size;
+                    let y = (c.row as f64) * size;
+
+                    match mode {
+                        DrawMode::Background => {
+                            if colorize && !c.is_under {
+                                let ics: ICellStrong = Rc::clone(cell) as ICellStrong;
+                                backend.shape(&[(x, y), (x + size, y), (x + size, y + size), (x, y + size)], &formatter.background_color(&ics));
+                            }
+                        },
+                        DrawMode::Line => {
+                            let passages = Passages {
+                                north: c.is_linked_to(&c.north),
+                                south: c.is_linked_to(&c.south),
+                                east: c.is_linked_to(&c.east),
+                                west: c.is_linked_to(&c.west),
+                            };
+                            for (x1, y1, x2, y2) in inset_cell_lines(x, y, size, inset, &passages, !c.is_under) {
+                                backend.line(x1, y1, x2, y2);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl WeaveGrid {
+    pub fn new(rows: usize, columns: usize) -> WeaveGrid {
+        let mut grid = WeaveGrid {
+            cells: Vec::new(),
+            under_cells: Rc::new(RefCell::new(Vec::new())),
+            rows, columns,
+            _cells: None
+        };
+
+        grid.prepare_grid();
+        grid.configure_cells();
+        grid.create_cells();
+
+        grid
+    }
+
+    fn configure_cells(&mut self) {
+        for cell in self.each_weave_cell().iter() {
+            if let Some(cell) = cell {
+                let row = cell.borrow().row;
+                let col = cell.borrow().column;
+                let mut c = cell.borrow_mut();
+
+                if row > 0 {
+                    c.north = self.cells[row - 1][col].as_ref().map(|n| Rc::downgrade(n));
+                }
+                if row < self.rows - 1 {
+                    c.south = self.cells[row + 1][col].as_ref().map(|n| Rc::downgrade(n));
+                }
+                if col < self.columns - 1 {
+                    c.east = self.cells[row][col + 1].as_ref().map(|n| Rc::downgrade(n));
+                }
+                if col > 0 {
+                    c.west = self.cells[row][col - 1].as_ref().map(|n| Rc::downgrade(n));
+                }
+            }
+        }
+    }
+
+    /// The grid's own cells followed by any under cells.
+    fn each_weave_cell(&self) -> Vec<Option<WeaveCellStrong>> {
+        self.cells.iter()
+            .flatten()
+            .cloned()
+            .chain(self.under_cells.borrow().iter().map(|c| Some(Rc::clone(c))))
+            .collect()
+    }
+
+    fn create_cells(&mut self) {
+        if self._cells.is_some() {
+            return;
+        }
+
+        self._cells = Some(
+            self.cells.iter().map(|row|
+                row.iter().map(|c| {
+                    if let Some(c) = c {
+                        return Some(Rc::clone(&c) as ICellStrong);
+                    }
+                    None
+                }).collect()
+            ).collect());
+    }
+}
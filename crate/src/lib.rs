@@ -4,13 +4,16 @@
 #![allow(dead_code)]
 #[macro_use]
 use std::cell::RefCell;
-use crate::rng::wasm_rng;
+use crate::rng::{RngWrapper, wasm_rng, seeded_rng::SeededRng};
 use wasm_bindgen::prelude::*;
 
 mod algorithms;
 mod rng;
 mod grid;
 mod cells;
+mod solvers;
+mod analysis;
+mod save;
 mod tests;
 
 use crate::grid::{Grid,
@@ -20,10 +23,14 @@ use crate::grid::{Grid,
     polar_grid::*,
     hex_grid::*,
     triangle_grid::*,
+    weave_grid::*,
     GridType,
+    raster::RasterOptions,
     mask_canvas::{clear_mask, append_mask_canvas}
 };
-use crate::algorithms::{MazeAlgorithm, recursive_backtracker::*, aldous_broder::*, hunt_and_kill::*, wilson::*};
+use crate::cells::ICellStrong;
+use crate::save::{SavedMaze, GridKind};
+use crate::algorithms::{MazeAlgorithm, Corner, steps::{GenerationEvent, record_events, replay}, binary_tree::*, sidewinder::*, recursive_backtracker::*, aldous_broder::*, hunt_and_kill::*, wilson::*, kruskal::*, prims::*, growing_tree::*, recursive_division::*, houston::*};
 
 cfg_if::cfg_if! {
     // When the `console_error_panic_hook` feature is enabled, we can call the
@@ -50,20 +57,31 @@ thread_local! {
     static GRID: RefCell<Option<Box<dyn Grid>>> = RefCell::new(None);
     pub static COLORIZE: RefCell<bool> = RefCell::new(true);
     pub static GRID_TYPE: RefCell<GridType> = RefCell::new(GridType::StandardGrid);
+    pub static BRAID: RefCell<f64> = RefCell::new(0.1);
+    static REPLAY: RefCell<Option<Replay>> = RefCell::new(None);
+}
+
+/// A maze being carved again on screen from the events its algorithm recorded.
+struct Replay {
+    grid: Box<dyn Grid>,
+    events: std::vec::IntoIter<GenerationEvent>,
 }
 
 
 /****** ALGORITHMS ******/
-// This is still broken for the polar grid.
-// #[wasm_bindgen]
-// pub fn basic_binary_tree(rows: usize, columns: usize) {
-//     build_and_display_grid(BinaryTree, rows, columns);    
-// }
+#[wasm_bindgen]
+pub fn basic_binary_tree(rows: usize, columns: usize, corner: &str) -> Result<(), JsValue> {
+    let corner = parse_corner(corner).map_err(|e| JsValue::from_str(&e))?;
+    build_and_display_grid(BinaryTree::new(corner), rows, columns);
+    Ok(())
+}
 
-// #[wasm_bindgen]
-// pub fn sidewinder(rows: usize, columns: usize) {
-//     build_and_display_grid(Sidewinder, rows, columns);
-// }
+#[wasm_bindgen]
+pub fn sidewinder(rows: usize, columns: usize, corner: &str) -> Result<(), JsValue> {
+    let corner = parse_corner(corner).map_err(|e| JsValue::from_str(&e))?;
+    build_and_display_grid(Sidewinder::new(corner), rows, columns);
+    Ok(())
+}
 
 #[wasm_bindgen]
 pub fn aldous_broder(rows: usize, columns: usize) {
@@ -85,6 +103,104 @@ pub fn recursive_backtracker(rows: usize, columns: usize) {
     build_and_display_grid(RecursiveBacktracker, rows, columns);
 }
 
+#[wasm_bindgen]
+pub fn kruskal(rows: usize, columns: usize) {
+    match get_grid_type() {
+        GridType::WeaveGrid => build_and_display_grid(WeaveKruskal, rows, columns),
+        _ => build_and_display_grid(Kruskal, rows, columns)
+    }
+}
+
+#[wasm_bindgen]
+pub fn simplified_prims(rows: usize, columns: usize) {
+    build_and_display_grid(SimplifiedPrims, rows, columns);
+}
+
+#[wasm_bindgen]
+pub fn true_prims(rows: usize, columns: usize) {
+    build_and_display_grid(TruePrims::default(), rows, columns);
+}
+
+#[wasm_bindgen]
+pub fn growing_tree(rows: usize, columns: usize) {
+    let selector = Weighted::new(vec![(75, Box::new(Newest)), (25, Box::new(Random))]);
+    build_and_display_grid(GrowingTree::new(Box::new(selector)), rows, columns);
+}
+
+/// Fails on anything but the standard grid, since recursive division splits along rows and columns.
+#[wasm_bindgen]
+pub fn recursive_division(rows: usize, columns: usize) -> Result<(), JsValue> {
+    check_grid_type("recursive_division", &get_grid_type()).map_err(|e| JsValue::from_str(&e))?;
+    build_and_display_grid(RecursiveDivision::new(5), rows, columns);
+    Ok(())
+}
+
+#[wasm_bindgen]
+pub fn houston(rows: usize, columns: usize) {
+    build_and_display_grid(Houston::default(), rows, columns);
+}
+
+/// Builds the maze for `seed`, which comes out the same on every platform.
+/// `algorithm` is the name of one of the functions above, e.g. "recursive_backtracker".
+#[wasm_bindgen]
+pub fn generate(seed: u32, algorithm: &str, rows: usize, columns: usize) -> Result<(), JsValue> {
+    check_grid_type(algorithm, &get_grid_type()).map_err(|e| JsValue::from_str(&e))?;
+    let alg = parse_algorithm(algorithm).map_err(|e| JsValue::from_str(&e))?;
+    build_and_display_grid_with(&*alg, rows, columns, &SeededRng::new(u64::from(seed)));
+    Ok(())
+}
+
+/// Builds the maze for `seed` like `generate` and keeps the events its algorithm went through,
+/// so `replay_step` can carve it again one passage at a time. Returns how many events there are.
+#[wasm_bindgen]
+pub fn record_maze(seed: u32, algorithm: &str, rows: usize, columns: usize) -> Result<usize, JsValue> {
+    set_panic_hook();
+    check_grid_type(algorithm, &get_grid_type()).map_err(|e| JsValue::from_str(&e))?;
+    let alg = parse_algorithm(algorithm).map_err(|e| JsValue::from_str(&e))?;
+    let rng = SeededRng::new(u64::from(seed));
+
+    let grid = new_grid(rows, columns);
+    let events = record_events(&*alg, &*grid, &rng);
+    grid.braid(get_braid(), &rng);
+    GRID.with(|slf| {
+        *slf.borrow_mut() = Some(grid);
+    });
+
+    let count = events.len();
+    REPLAY.with(|replay| {
+        *replay.borrow_mut() = Some(Replay { grid: new_grid(rows, columns), events: events.into_iter() });
+    });
+    Ok(count)
+}
+
+/// Replays the recorded maze up to its next new or removed passage and draws it.
+/// Returns false once there is nothing left to replay, after drawing the finished maze, braid included.
+#[wasm_bindgen]
+pub fn replay_step() -> bool {
+    let more = REPLAY.with(|slf| match slf.borrow_mut().as_mut() {
+        Some(Replay { grid, events }) => {
+            let changed = events.any(|event| replay(&**grid, &event));
+            if changed {
+                grid.to_web(&prepare_distance_grid(&**grid), false);
+            }
+            Some(changed)
+        },
+        None => None
+    });
+
+    match more {
+        Some(true) => true,
+        Some(false) => {
+            REPLAY.with(|slf| {
+                *slf.borrow_mut() = None;
+            });
+            redisplay_grid();
+            false
+        },
+        None => false
+    }
+}
+
 /****** OTHER FEATURES ******/
 
 #[wasm_bindgen]
@@ -107,6 +223,14 @@ pub fn on_colorize_change(colorize: bool) {
     redisplay_grid();
 }
 
+/// Sets the chance (0.0 to 1.0) of removing each dead end from the next maze.
+#[wasm_bindgen]
+pub fn on_braid_change(braid: f64) {
+    BRAID.with(|b| {
+        *b.borrow_mut() = braid.max(0.0).min(1.0);
+    });
+}
+
 #[wasm_bindgen]
 pub fn on_grid_type_change(grid_type: &str) {
     clear_mask();
@@ -117,12 +241,60 @@ pub fn on_grid_type_change(grid_type: &str) {
         "polar" => GridType::PolarGrid,
         "hex" => GridType::HexGrid,
         "triangle" => GridType::TriangleGrid,
+        "weave" => GridType::WeaveGrid,
         _ => GridType::PolarGrid
     };
 
     set_grid_type(grid_type);
 }
 
+/// The maze on screen in the JSON save format, or an empty string if there isn't one yet.
+#[wasm_bindgen]
+pub fn save_maze() -> String {
+    GRID.with(|grid| grid.borrow().as_ref()
+        .map_or(String::new(), |grid| SavedMaze::from_grid(&**grid).to_json()))
+}
+
+/// The maze on screen as an SVG document, colored as on the page, or an empty string if there isn't one yet.
+#[wasm_bindgen]
+pub fn export_svg() -> String {
+    GRID.with(|grid| grid.borrow().as_ref()
+        .map_or(String::new(), |grid| grid.to_svg(&prepare_distance_grid(&**grid), get_colorize())))
+}
+
+/// The maze on screen as a PNG image, with cells and walls the given number of pixels wide,
+/// or no bytes if there isn't a maze yet.
+#[wasm_bindgen]
+pub fn export_png(cell_size: f64, wall_width: f64) -> Vec<u8> {
+    let options = RasterOptions { cell_size, wall_width, colorize: get_colorize(), ..RasterOptions::default() };
+    GRID.with(|grid| grid.borrow().as_ref()
+        .map_or(Vec::new(), |grid| grid.to_raster(&prepare_distance_grid(&**grid), &options).to_png()))
+}
+
+/// Shows a maze saved by `save_maze`, switching to its grid type.
+#[wasm_bindgen]
+pub fn load_maze(json: &str) -> Result<(), JsValue> {
+    set_panic_hook();
+
+    let saved = SavedMaze::from_json(json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let grid = saved.to_grid().map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    cleanup_canvas(&get_grid_type());
+    set_grid_type(match saved.kind {
+        GridKind::Standard | GridKind::Masked => GridType::StandardGrid,
+        GridKind::Polar => GridType::PolarGrid,
+        GridKind::Hex => GridType::HexGrid,
+        GridKind::Triangle => GridType::TriangleGrid,
+        GridKind::Weave => GridType::WeaveGrid,
+    });
+
+    GRID.with(|slf| {
+        *slf.borrow_mut() = Some(grid);
+    });
+    redisplay_grid();
+    Ok(())
+}
+
 #[wasm_bindgen]
 pub fn add_mask_canvas() {
     append_mask_canvas();
@@ -131,25 +303,81 @@ pub fn add_mask_canvas() {
 /****** HELPERS ******/
 
 fn build_and_display_grid(alg: impl MazeAlgorithm, rows: usize, columns: usize) {
+    build_and_display_grid_with(&alg, rows, columns, &wasm_rng::WasmRng);
+}
+
+fn build_and_display_grid_with(alg: &dyn MazeAlgorithm, rows: usize, columns: usize, rng: &dyn RngWrapper<Shuffle=ICellStrong>) {
     set_panic_hook();
 
     GRID.with(|grid| match grid.borrow_mut() {
         mut grid_mut_ref => {
-            *grid_mut_ref = match get_grid_type() {
-                GridType::PolarGrid => Some(Box::new(PolarGrid::new(rows, columns))),
-                GridType::HexGrid => Some(Box::new(HexGrid::new(rows, columns))),
-                GridType::TriangleGrid => Some(Box::new(TriangleGrid::new(rows, columns))),
-                GridType::StandardGrid => Some(Box::new(StandardGrid::new(rows, columns))),
-            };
-            render_grid(&**grid_mut_ref.as_ref().unwrap(), alg);
+            *grid_mut_ref = Some(new_grid(rows, columns));
+            render_grid(&**grid_mut_ref.as_ref().unwrap(), alg, rng);
         }
     });
 }
 
+/// An empty grid of the selected type.
+fn new_grid(rows: usize, columns: usize) -> Box<dyn Grid> {
+    match get_grid_type() {
+        GridType::PolarGrid => Box::new(PolarGrid::new(rows, columns)),
+        GridType::HexGrid => Box::new(HexGrid::new(rows, columns)),
+        GridType::TriangleGrid => Box::new(TriangleGrid::new(rows, columns)),
+        GridType::StandardGrid => Box::new(StandardGrid::new(rows, columns)),
+        GridType::WeaveGrid => Box::new(WeaveGrid::new(rows, columns)),
+    }
+}
+
+/// Fails for names that aren't one of the algorithm functions above.
+fn parse_algorithm(algorithm: &str) -> Result<Box<dyn MazeAlgorithm>, String> {
+    Ok(match algorithm {
+        "basic_binary_tree" => Box::new(BinaryTree::default()),
+        "sidewinder" => Box::new(Sidewinder::default()),
+        "aldous_broder" => Box::new(AldousBroder),
+        "wilson" => Box::new(Wilson),
+        "hunt_and_kill" => Box::new(HuntAndKill),
+        "kruskal" => match get_grid_type() {
+            GridType::WeaveGrid => Box::new(WeaveKruskal),
+            _ => Box::new(Kruskal)
+        },
+        "simplified_prims" => Box::new(SimplifiedPrims),
+        "true_prims" => Box::new(TruePrims::default()),
+        "growing_tree" => Box::new(GrowingTree::new(Box::new(Weighted::new(vec![(75, Box::new(Newest)), (25, Box::new(Random))])))),
+        "recursive_division" => Box::new(RecursiveDivision::new(5)),
+        "houston" => Box::new(Houston::default()),
+        "recursive_backtracker" => Box::new(RecursiveBacktracker),
+        _ => return Err(format!("unknown algorithm: {}", algorithm))
+    })
+}
+
+/// Fails for algorithms that can't carve the given type of grid.
+fn check_grid_type(algorithm: &str, grid_type: &GridType) -> Result<(), String> {
+    match (algorithm, grid_type) {
+        ("recursive_division", GridType::StandardGrid) => Ok(()),
+        ("recursive_division", _) => Err(String::from("recursive division only works on the standard grid")),
+        _ => Ok(())
+    }
+}
+
+/// Fails for anything but "ne", "nw", "se" and "sw".
+fn parse_corner(corner: &str) -> Result<Corner, String> {
+    match corner {
+        "ne" => Ok(Corner::NorthEast),
+        "nw" => Ok(Corner::NorthWest),
+        "se" => Ok(Corner::SouthEast),
+        "sw" => Ok(Corner::SouthWest),
+        _ => Err(format!("unknown corner: {}", corner))
+    }
+}
+
 pub fn get_colorize() -> bool {
     COLORIZE.with(|c| *c.borrow())
 }
 
+pub fn get_braid() -> f64 {
+    BRAID.with(|b| *b.borrow())
+}
+
 pub fn get_grid_type() -> GridType {
     GRID_TYPE.with(|t| t.borrow().clone())
 }
@@ -160,10 +388,9 @@ pub fn set_grid_type(new_type: GridType) {
     });
 }
 
-fn render_grid(grid: &dyn Grid, alg: impl MazeAlgorithm) {
-    let wasm_generator = wasm_rng::WasmRng;
-    alg.on(grid, &wasm_generator);
-    grid.braid(0.1f64, &wasm_generator);
+fn render_grid(grid: &dyn Grid, alg: &dyn MazeAlgorithm, rng: &dyn RngWrapper<Shuffle=ICellStrong>) {
+    alg.on(grid, rng);
+    grid.braid(get_braid(), rng);
     let distance_grid = prepare_distance_grid(grid);
     
     grid.to_web(&distance_grid, get_colorize());
@@ -1,9 +1,16 @@
 pub mod wasm_rng;
 pub mod thread_rng;
+pub mod seeded_rng;
 
 // This allows me to run both `cargo test` and `npm start` without having to switch out RNG implementations
 pub trait RngWrapper {
     type Shuffle;
     fn gen_range(&self, min: usize, max: usize) -> usize;
     fn shuffle(&self, vec: &mut Vec<Self::Shuffle>);
-}
\ No newline at end of file
+
+    /// A number in `0.0..1.0`, built from `gen_range` so seeded generators stay reproducible.
+    fn gen_f64(&self) -> f64 {
+        const STEPS: usize = 1 << 24;
+        self.gen_range(0, STEPS) as f64 / STEPS as f64
+    }
+}
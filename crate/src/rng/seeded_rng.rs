@@ -0,0 +1,52 @@
+use crate::cells::ICellStrong;
+use crate::rng::*;
+use std::cell::Cell;
+
+/// A SplitMix64 generator. Everything is done in `u64` arithmetic, so a seed gives the same
+/// sequence natively and in wasm, where `usize` is only 32 bits wide.
+pub struct SeededRng {
+    state: Cell<u64>
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> SeededRng {
+        SeededRng { state: Cell::new(seed) }
+    }
+
+    pub fn next_u64(&self) -> u64 {
+        let state = self.state.get().wrapping_add(0x9E37_79B9_7F4A_7C15);
+        self.state.set(state);
+
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl RngWrapper for SeededRng {
+    type Shuffle = ICellStrong;
+
+    fn gen_range(&self, min: usize, max: usize) -> usize {
+        if max <= min {
+            panic!("gen_range called with an empty range: {}..{}", min, max);
+        }
+
+        let range = (max - min) as u64;
+        // Throw away the top values that would make some results more likely than others.
+        let zone = u64::max_value() - (u64::max_value() % range);
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return min + (value % range) as usize;
+            }
+        }
+    }
+
+    fn shuffle(&self, vec: &mut Vec<Self::Shuffle>) {
+        for i in (1..vec.len()).rev() {
+            let j = self.gen_range(0, i + 1);
+            vec.swap(i, j);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! The binary form of a saved maze. All numbers are unsigned LEB128 varints unless noted.
+//!
+//! ```text
+//! magic    4 bytes  "MAZE"
+//! version  varint
+//! grid     1 byte   0 standard, 1 masked, 2 polar, 3 hex, 4 triangle, 5 weave
+//! rows     varint
+//! columns  varint
+//! flags    1 byte   bit 0: mask follows, bit 1: start follows, bit 2: goal follows
+//! mask     rows * columns bits, row by row, lowest bit first, padded to a whole byte
+//! start    varint row, varint column
+//! goal     varint row, varint column
+//! links    varint count, then row, column, row, column for each
+//! ```
+
+use super::{SavedMaze, GridKind, LoadError, VERSION, check_size};
+
+const MAGIC: &[u8] = b"MAZE";
+const HAS_MASK: u8 = 1;
+const HAS_START: u8 = 2;
+const HAS_GOAL: u8 = 4;
+
+pub fn to_bytes(maze: &SavedMaze) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    write_varint(&mut bytes, VERSION as usize);
+    bytes.push(maze.kind.code());
+    write_varint(&mut bytes, maze.rows);
+    write_varint(&mut bytes, maze.columns);
+
+    let flags = [(maze.mask.is_some(), HAS_MASK), (maze.start.is_some(), HAS_START), (maze.goal.is_some(), HAS_GOAL)].iter()
+        .filter(|(present, _)| *present)
+        .fold(0, |acc, (_, flag)| acc | flag);
+    bytes.push(flags);
+
+    if let Some(mask) = maze.mask.as_ref() {
+        let mut packed = vec![0u8; (maze.rows * maze.columns + 7) / 8];
+        for i in 0..maze.rows {
+            for j in 0..maze.columns {
+                if mask.get(i).and_then(|row| row.get(j)).cloned().unwrap_or(false) {
+                    let bit = i * maze.columns + j;
+                    packed[bit / 8] |= 1 << (bit % 8);
+                }
+            }
+        }
+        bytes.extend(packed);
+    }
+
+    for (row, column) in maze.start.iter().chain(maze.goal.iter()) {
+        write_varint(&mut bytes, *row);
+        write_varint(&mut bytes, *column);
+    }
+
+    write_varint(&mut bytes, maze.links.len());
+    for (a, b) in maze.links.iter() {
+        for n in [a.0, a.1, b.0, b.1].iter() {
+            write_varint(&mut bytes, *n);
+        }
+    }
+
+    bytes
+}
+
+pub fn from_bytes(bytes: &[u8]) -> Result<SavedMaze, LoadError> {
+    let mut reader = Reader { bytes, position: 0 };
+
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(LoadError::Malformed { position: 0, message: String::from("not a saved maze") });
+    }
+
+    let version = reader.varint()?;
+    if version > VERSION as usize {
+        return Err(LoadError::UnsupportedVersion(version as u32));
+    }
+
+    let code = reader.take(1)?[0];
+    let kind = GridKind::from_code(code).ok_or_else(|| LoadError::UnknownGrid(code.to_string()))?;
+    let size_position = reader.position;
+    let rows = reader.varint()?;
+    let columns = reader.varint()?;
+    check_size(rows, columns, size_position)?;
+    let flags = reader.take(1)?[0];
+
+    let mask = if flags & HAS_MASK != 0 {
+        let cells = rows.checked_mul(columns)
+            .ok_or_else(|| LoadError::Malformed { position: reader.position, message: String::from("mask too large") })?;
+        let packed = reader.take((cells + 7) / 8)?;
+        Some((0..rows)
+            .map(|i| (0..columns).map(|j| {
+                let bit = i * columns + j;
+                packed[bit / 8] & (1 << (bit % 8)) != 0
+            }).collect())
+            .collect())
+    } else {
+        None
+    };
+
+    let start = if flags & HAS_START != 0 { Some((reader.varint()?, reader.varint()?)) } else { None };
+    let goal = if flags & HAS_GOAL != 0 { Some((reader.varint()?, reader.varint()?)) } else { None };
+
+    let count = reader.varint()?;
+    let mut links = Vec::new();
+    for _ in 0..count {
+        let a = (reader.varint()?, reader.varint()?);
+        let b = (reader.varint()?, reader.varint()?);
+        links.push((a, b));
+    }
+
+    Ok(SavedMaze { kind, rows, columns, mask, links, start, goal })
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut n: usize) {
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            bytes.push(byte);
+            return;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], LoadError> {
+        if self.bytes.len() - self.position < count {
+            return Err(LoadError::Malformed { position: self.position, message: String::from("unexpected end of data") });
+        }
+        let taken = &self.bytes[self.position..self.position + count];
+        self.position += count;
+        Ok(taken)
+    }
+
+    fn varint(&mut self) -> Result<usize, LoadError> {
+        let start = self.position;
+        let mut n: usize = 0;
+        for shift in (0..std::mem::size_of::<usize>() * 8).step_by(7) {
+            let byte = self.take(1)?[0];
+            n |= ((byte & 0x7f) as usize) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err(LoadError::Malformed { position: start, message: String::from("number too long") })
+    }
+}
@@ -0,0 +1,311 @@
+//! The JSON form of a saved maze:
+//!
+//! ```text
+//! {
+//!   "format": "rust-mazes",
+//!   "version": 1,
+//!   "grid": "standard" | "masked" | "polar" | "hex" | "triangle" | "weave",
+//!   "rows": 5,
+//!   "columns": 5,
+//!   "mask": null | ["X...X", ".....", ...],
+//!   "links": [[row, column, row, column], ...],
+//!   "start": null | [row, column],
+//!   "goal": null | [row, column]
+//! }
+//! ```
+//!
+//! The mask has one string per row, with `X` for a missing cell and `.` for a cell, like `mask.txt`.
+//! `mask`, `start` and `goal` may be left out. Unknown fields are ignored.
+
+use super::{SavedMaze, GridKind, LoadError, VERSION, check_size};
+
+const FORMAT: &str = "rust-mazes";
+// Saved mazes nest three deep at most. Anything much deeper is junk, and is turned away before it can run out of stack.
+const MAX_DEPTH: usize = 32;
+
+pub fn to_json(maze: &SavedMaze) -> String {
+    let position = |p: Option<(usize, usize)>| p.map_or(String::from("null"), |(row, column)| format!("[{}, {}]", row, column));
+
+    let mask = match maze.mask.as_ref() {
+        Some(mask) => {
+            let rows: Vec<String> = mask.iter()
+                .map(|row| format!("    \"{}\"", row.iter().map(|on| if *on { '.' } else { 'X' }).collect::<String>()))
+                .collect();
+            format!("[\n{}\n  ]", rows.join(",\n"))
+        },
+        None => String::from("null")
+    };
+
+    let links: Vec<String> = maze.links.iter()
+        .map(|(a, b)| format!("    [{}, {}, {}, {}]", a.0, a.1, b.0, b.1))
+        .collect();
+    let links = if links.is_empty() { String::from("[]") } else { format!("[\n{}\n  ]", links.join(",\n")) };
+
+    format!("{{\n  \"format\": \"{}\",\n  \"version\": {},\n  \"grid\": \"{}\",\n  \"rows\": {},\n  \"columns\": {},\n  \"mask\": {},\n  \"links\": {},\n  \"start\": {},\n  \"goal\": {}\n}}\n",
+        FORMAT, VERSION, maze.kind.name(), maze.rows, maze.columns, mask, links, position(maze.start), position(maze.goal))
+}
+
+pub fn from_json(text: &str) -> Result<SavedMaze, LoadError> {
+    let mut parser = Parser { text: text.as_bytes(), position: 0, depth: 0 };
+    let root = parser.value()?;
+    parser.skip_whitespace();
+    if parser.position < text.len() {
+        return Err(parser.error("unexpected text after the maze"));
+    }
+
+    let fields = match &root.value {
+        Value::Object(fields) => fields,
+        _ => return Err(mismatch(&root, "expected an object"))
+    };
+    let field = |name: &'static str| fields.iter().find(|(n, _)| n == name).map(|(_, v)| v).filter(|v| v.value != Value::Null);
+    let required = |name: &'static str| field(name).ok_or(LoadError::MissingField(name));
+
+    let version = number(required("version")?)?;
+    if version > VERSION as usize {
+        return Err(LoadError::UnsupportedVersion(version as u32));
+    }
+
+    let grid = required("grid")?;
+    let grid = match &grid.value {
+        Value::String(name) => name,
+        _ => return Err(mismatch(grid, "grid should be a string"))
+    };
+    let kind = GridKind::from_name(grid).ok_or_else(|| LoadError::UnknownGrid(grid.clone()))?;
+
+    let mask = match field("mask") {
+        Some(Node { value: Value::Array(rows), .. }) => Some(rows.iter()
+            .map(|row| match &row.value {
+                Value::String(bits) => Ok(bits.chars().map(|c| c != 'X').collect()),
+                _ => Err(mismatch(row, "mask rows should be strings"))
+            })
+            .collect::<Result<Vec<Vec<bool>>, LoadError>>()?),
+        Some(mask) => return Err(mismatch(mask, "mask should be an array")),
+        None => None
+    };
+
+    let links = array(required("links")?)?.iter()
+        .map(|link| match numbers(link)?.as_slice() {
+            [r1, c1, r2, c2] => Ok(((*r1, *c1), (*r2, *c2))),
+            _ => Err(mismatch(link, "links should be [row, column, row, column]"))
+        })
+        .collect::<Result<Vec<_>, LoadError>>()?;
+
+    let position = |name: &'static str| -> Result<Option<(usize, usize)>, LoadError> {
+        match field(name) {
+            Some(value) => match numbers(value)?.as_slice() {
+                [row, column] => Ok(Some((*row, *column))),
+                _ => Err(mismatch(value, "positions should be [row, column]"))
+            },
+            None => Ok(None)
+        }
+    };
+
+    let (rows, columns) = (required("rows")?, required("columns")?);
+    let (size_position, rows, columns) = (rows.position, number(rows)?, number(columns)?);
+    check_size(rows, columns, size_position)?;
+
+    Ok(SavedMaze {
+        kind,
+        rows,
+        columns,
+        mask,
+        links,
+        start: position("start")?,
+        goal: position("goal")?,
+    })
+}
+
+#[derive(Debug, PartialEq)]
+enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Node>),
+    Object(Vec<(String, Node)>),
+}
+
+/// A value and the byte offset it starts at, so errors can point at it.
+#[derive(Debug, PartialEq)]
+struct Node {
+    position: usize,
+    value: Value,
+}
+
+fn mismatch(node: &Node, message: &str) -> LoadError {
+    LoadError::Malformed { position: node.position, message: String::from(message) }
+}
+
+fn number(node: &Node) -> Result<usize, LoadError> {
+    match node.value {
+        Value::Number(n) if n >= 0.0 && n.fract() == 0.0 => Ok(n as usize),
+        _ => Err(mismatch(node, "expected a whole number"))
+    }
+}
+
+fn array(node: &Node) -> Result<&Vec<Node>, LoadError> {
+    match &node.value {
+        Value::Array(values) => Ok(values),
+        _ => Err(mismatch(node, "expected an array"))
+    }
+}
+
+fn numbers(node: &Node) -> Result<Vec<usize>, LoadError> {
+    array(node)?.iter().map(number).collect()
+}
+
+/// Just enough of a JSON reader for saved mazes.
+struct Parser<'a> {
+    text: &'a [u8],
+    position: usize,
+    // How many arrays and objects the parser is inside.
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: &str) -> LoadError {
+        LoadError::Malformed { position: self.position, message: String::from(message) }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.position < self.text.len() && (self.text[self.position] as char).is_whitespace() {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.text.get(self.position).cloned()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), LoadError> {
+        if self.peek() == Some(byte) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", byte as char)))
+        }
+    }
+
+    fn keyword(&mut self, word: &str, value: Value) -> Result<Value, LoadError> {
+        if self.text[self.position..].starts_with(word.as_bytes()) {
+            self.position += word.len();
+            Ok(value)
+        } else {
+            Err(self.error("unexpected character"))
+        }
+    }
+
+    fn value(&mut self) -> Result<Node, LoadError> {
+        let byte = self.peek();
+        let position = self.position;
+        let value = match byte {
+            Some(b'{') => self.nested(Parser::object),
+            Some(b'[') => self.nested(Parser::array),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b't') => self.keyword("true", Value::Bool(true)),
+            Some(b'f') => self.keyword("false", Value::Bool(false)),
+            Some(b'n') => self.keyword("null", Value::Null),
+            Some(b'-') | Some(b'0'..=b'9') => self.number(),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of input")),
+        }?;
+        Ok(Node { position, value })
+    }
+
+    fn nested(&mut self, parse: fn(&mut Parser<'a>) -> Result<Value, LoadError>) -> Result<Value, LoadError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("nested too deeply"));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn object(&mut self) -> Result<Value, LoadError> {
+        self.expect(b'{')?;
+        let mut fields = Vec::new();
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Ok(Value::Object(fields));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let name = self.string()?;
+            self.expect(b':')?;
+            fields.push((name, self.value()?));
+
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b'}') => { self.position += 1; return Ok(Value::Object(fields)); },
+                _ => return Err(self.error("expected ',' or '}'"))
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, LoadError> {
+        self.expect(b'[')?;
+        let mut values = Vec::new();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(Value::Array(values));
+        }
+
+        loop {
+            values.push(self.value()?);
+
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => { self.position += 1; return Ok(Value::Array(values)); },
+                _ => return Err(self.error("expected ',' or ']'"))
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, LoadError> {
+        if self.text.get(self.position) != Some(&b'"') {
+            return Err(self.error("expected a string"));
+        }
+        self.position += 1;
+
+        let mut bytes = Vec::new();
+        loop {
+            match self.text.get(self.position).cloned() {
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    self.position += 1;
+                    let escaped = match self.text.get(self.position) {
+                        Some(b'n') => b'\n',
+                        Some(b't') => b'\t',
+                        Some(b'r') => b'\r',
+                        Some(b'"') => b'"',
+                        Some(b'\\') => b'\\',
+                        Some(b'/') => b'/',
+                        _ => return Err(self.error("unsupported escape"))
+                    };
+                    bytes.push(escaped);
+                },
+                Some(byte) => bytes.push(byte),
+                None => return Err(self.error("unterminated string"))
+            }
+            self.position += 1;
+        }
+        self.position += 1;
+
+        String::from_utf8(bytes).map_err(|_| self.error("invalid UTF-8"))
+    }
+
+    fn number(&mut self) -> Result<Value, LoadError> {
+        let start = self.position;
+        while self.position < self.text.len() && b"+-.eE0123456789".contains(&self.text[self.position]) {
+            self.position += 1;
+        }
+
+        std::str::from_utf8(&self.text[start..self.position]).ok()
+            .and_then(|n| n.parse::<f64>().ok())
+            .map(Value::Number)
+            .ok_or_else(|| LoadError::Malformed { position: start, message: String::from("invalid number") })
+    }
+}
@@ -0,0 +1,275 @@
+//! Saving finished mazes and loading them back.
+//!
+//! A maze is saved as a `SavedMaze`, which can be written as JSON (`json`) or in a compact binary form (`binary`).
+//! Both hold the same fields and carry `VERSION`, so older files can be recognised when the format changes.
+//!
+//! Cells are given by (row, column), as in `Grid::get_cell`. Each passage is listed once, as the two cells it joins.
+//! On a weave grid, a passage that tunnels under a cell is listed as the two cells either side of the tunnel,
+//! rather than through the under cell, which only exists once the tunnel has been carved.
+
+pub mod json;
+pub mod binary;
+
+use crate::grid::{Grid, mask::Mask, masked_grid::MaskedGrid, standard_grid::StandardGrid, polar_grid::PolarGrid, hex_grid::HexGrid, triangle_grid::TriangleGrid, weave_grid::WeaveGrid};
+use crate::cells::{self, ICellStrong, cell::Cell, polar_cell::PolarCell, hex_cell::HexCell, triangle_cell::TriangleCell, weave_cell::WeaveCell};
+use std::fmt::{self, Display, Formatter};
+
+/// The version written by this code. Loading rejects anything newer.
+pub const VERSION: u32 = 1;
+
+/// The most rows or columns a saved maze can have. Anything bigger is taken to be corrupt rather than allocated.
+pub const MAX_SIDE: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridKind {
+    Standard,
+    Masked,
+    Polar,
+    Hex,
+    Triangle,
+    Weave,
+}
+
+impl GridKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            GridKind::Standard => "standard",
+            GridKind::Masked => "masked",
+            GridKind::Polar => "polar",
+            GridKind::Hex => "hex",
+            GridKind::Triangle => "triangle",
+            GridKind::Weave => "weave",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<GridKind> {
+        KINDS.iter().cloned().find(|k| k.name() == name)
+    }
+
+    fn code(self) -> u8 {
+        KINDS.iter().position(|k| *k == self).unwrap() as u8
+    }
+
+    fn from_code(code: u8) -> Option<GridKind> {
+        KINDS.get(code as usize).cloned()
+    }
+}
+
+// Binary files store a kind as its index here, so only ever add to the end.
+const KINDS: [GridKind; 6] = [GridKind::Standard, GridKind::Masked, GridKind::Polar, GridKind::Hex, GridKind::Triangle, GridKind::Weave];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedMaze {
+    pub kind: GridKind,
+    pub rows: usize,
+    pub columns: usize,
+    /// Which cells exist, for masked grids. `None` means every cell does.
+    pub mask: Option<Vec<Vec<bool>>>,
+    /// Each passage once, as ((row, column), (row, column)).
+    pub links: Vec<((usize, usize), (usize, usize))>,
+    pub start: Option<(usize, usize)>,
+    pub goal: Option<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadError {
+    /// The data couldn't be read at all. `position` is the byte offset the problem was found at,
+    /// or 0 for a `SavedMaze` that didn't come from bytes.
+    Malformed { position: usize, message: String },
+    /// Written by a newer version than this one understands.
+    UnsupportedVersion(u32),
+    UnknownGrid(String),
+    MissingField(&'static str),
+    /// A cell that isn't on the grid.
+    NoSuchCell(usize, usize),
+    /// A passage between two cells that can't be joined.
+    InvalidLink((usize, usize), (usize, usize)),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            LoadError::Malformed { position, message } => write!(f, "malformed maze at byte {}: {}", position, message),
+            LoadError::UnsupportedVersion(v) => write!(f, "maze format version {} is newer than {}", v, VERSION),
+            LoadError::UnknownGrid(name) => write!(f, "unknown grid type \"{}\"", name),
+            LoadError::MissingField(name) => write!(f, "missing field \"{}\"", name),
+            LoadError::NoSuchCell(row, column) => write!(f, "no cell at ({}, {})", row, column),
+            LoadError::InvalidLink(a, b) => write!(f, "can't link {:?} to {:?}", a, b),
+        }
+    }
+}
+
+impl SavedMaze {
+    /// Records the maze on `grid`. Standard grids with cells cut out by `sparsify` are saved as masked grids.
+    pub fn from_grid(grid: &dyn Grid) -> SavedMaze {
+        let kind = kind_of(grid);
+        let (rows, columns) = match kind {
+            // Standard grids report rows and columns the other way around, so go by the cells themselves.
+            GridKind::Standard | GridKind::Masked => (grid.cells().len(), grid.cells().get(0).map_or(0, |r| r.len())),
+            _ => (grid.rows(), grid.columns()),
+        };
+
+        let mask = match kind {
+            GridKind::Masked => Some(grid.cells().iter().map(|row| row.iter().map(|c| c.is_some()).collect()).collect()),
+            _ => None
+        };
+
+        let mut links = Vec::new();
+        for cell in grid.each_cell().iter().filter_map(|c| c.as_ref()) {
+            let from = key_of(cell);
+            for link in cell.borrow().links().iter().filter_map(|l| l.as_ref()) {
+                match under_cell(link) {
+                    // Record the tunnel from one side only: the side with the lower position.
+                    Some(_) => {
+                        let far = other_side(link, from);
+                        if from < far {
+                            links.push((from, far));
+                        }
+                    },
+                    None if from.0 < rows || kind != GridKind::Weave => {
+                        let to = key_of(link);
+                        if from < to {
+                            links.push((from, to));
+                        }
+                    },
+                    None => {}
+                }
+            }
+        }
+        links.sort();
+
+        SavedMaze { kind, rows, columns, mask, links, start: None, goal: None }
+    }
+
+    pub fn to_json(&self) -> String {
+        json::to_json(self)
+    }
+
+    pub fn from_json(text: &str) -> Result<SavedMaze, LoadError> {
+        json::from_json(text)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        binary::to_bytes(self)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<SavedMaze, LoadError> {
+        binary::from_bytes(bytes)
+    }
+
+    /// Builds a grid of the saved type and carves the saved passages into it.
+    pub fn to_grid(&self) -> Result<Box<dyn Grid>, LoadError> {
+        check_size(self.rows, self.columns, 0)?;
+        let grid: Box<dyn Grid> = match self.kind {
+            GridKind::Standard => Box::new(StandardGrid::new(self.rows, self.columns)),
+            GridKind::Masked => {
+                let mut mask = Mask::new(self.rows, self.columns);
+                if let Some(bits) = self.mask.as_ref() {
+                    for (i, row) in bits.iter().enumerate().take(self.rows) {
+                        for (j, on) in row.iter().enumerate().take(self.columns) {
+                            mask.set(i, j, *on);
+                        }
+                    }
+                }
+                Box::new(MaskedGrid::new(mask))
+            },
+            GridKind::Polar => Box::new(PolarGrid::new(self.rows, self.columns)),
+            GridKind::Hex => Box::new(HexGrid::new(self.rows, self.columns)),
+            GridKind::Triangle => Box::new(TriangleGrid::new(self.rows, self.columns)),
+            GridKind::Weave => Box::new(WeaveGrid::new(self.rows, self.columns)),
+        };
+
+        // Columns are checked against the row first, since polar grids wrap them around the ring in `get_cell`.
+        // Rows past the end are left to `get_cell`, which is how a weave grid finds its under cells.
+        let cell = |(row, column): (usize, usize)| match grid.cells().get(row) {
+            Some(cells) if column >= cells.len() => None,
+            _ => grid.get_cell(row, column)
+        }.ok_or(LoadError::NoSuchCell(row, column));
+        for position in self.start.iter().chain(self.goal.iter()) {
+            cell(*position)?;
+        }
+
+        // Tunnels can only be dug once the passage they run under is there.
+        let (tunnels, passages): (Vec<_>, Vec<_>) = self.links.iter().partition(|(a, b)| self.kind == GridKind::Weave && is_tunnel(*a, *b));
+        for (a, b) in passages.iter() {
+            if !grid.link(&cell(*a)?, &cell(*b)?) {
+                return Err(LoadError::InvalidLink(*a, *b));
+            }
+        }
+
+        // Linked directly rather than through `Grid::link`: braiding may since have joined the cell a tunnel
+        // runs under to a third neighbor, and then it no longer counts as a place a new tunnel could go.
+        for (a, b) in tunnels.iter() {
+            let (from, to) = (cell(*a)?, cell(*b)?);
+            let before = from.borrow().links().len();
+            cells::link(&from, &to);
+            if from.borrow().links().len() == before {
+                return Err(LoadError::InvalidLink(*a, *b));
+            }
+        }
+
+        Ok(grid)
+    }
+}
+
+/// Fails for mazes with more than `MAX_SIDE` rows or columns, pointing at `position`.
+fn check_size(rows: usize, columns: usize, position: usize) -> Result<(), LoadError> {
+    if rows > MAX_SIDE || columns > MAX_SIDE {
+        let message = format!("{} by {} is bigger than the limit of {} by {}", rows, columns, MAX_SIDE, MAX_SIDE);
+        return Err(LoadError::Malformed { position, message });
+    }
+    Ok(())
+}
+
+fn kind_of(grid: &dyn Grid) -> GridKind {
+    let first = grid.each_cell().into_iter().filter_map(|c| c).next();
+    let first = match first {
+        Some(cell) => cell,
+        None => return GridKind::Standard
+    };
+    let cell = first.borrow();
+    let cell = cell.as_any();
+
+    if cell.is::<PolarCell>() {
+        GridKind::Polar
+    } else if cell.is::<HexCell>() {
+        GridKind::Hex
+    } else if cell.is::<TriangleCell>() {
+        GridKind::Triangle
+    } else if cell.is::<WeaveCell>() {
+        GridKind::Weave
+    } else if cell.is::<Cell>() && grid.cells().iter().flatten().any(|c| c.is_none()) {
+        GridKind::Masked
+    } else {
+        GridKind::Standard
+    }
+}
+
+fn key_of(cell: &ICellStrong) -> (usize, usize) {
+    let c = cell.borrow();
+    (c.row(), c.column())
+}
+
+fn under_cell(cell: &ICellStrong) -> Option<(usize, usize)> {
+    let c = cell.borrow();
+    match c.as_any().downcast_ref::<WeaveCell>() {
+        Some(w) if w.is_under => Some((w.row, w.column)),
+        _ => None
+    }
+}
+
+/// The cell at the other end of the tunnel through `under`, seen from `from`.
+fn other_side(under: &ICellStrong, from: (usize, usize)) -> (usize, usize) {
+    let links = under.borrow().links();
+    links.iter()
+        .filter_map(|l| l.as_ref())
+        .map(|l| key_of(l))
+        .find(|l| *l != from)
+        .unwrap_or(from)
+}
+
+/// Two cells in a straight line with one between them.
+fn is_tunnel(a: (usize, usize), b: (usize, usize)) -> bool {
+    let diff = |x: usize, y: usize| if x > y { x - y } else { y - x };
+    (a.0 == b.0 && diff(a.1, b.1) == 2) || (a.1 == b.1 && diff(a.0, b.0) == 2)
+}
@@ -0,0 +1,84 @@
+use crate::cells::ICellStrong;
+use crate::grid::Grid;
+use crate::rng::RngWrapper;
+use crate::solvers::{MazeSolver, SolverRun, SolverEvent, position, linked_neighbors};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+#[derive(Debug)]
+pub struct DeadEndFilling;
+
+/// Dead-End Filling
+/// Solves the maze from above instead of walking it.
+/// 1) Fill in every dead end other than the start and goal
+/// 2) Filling a dead end may turn the cell next to it into one, so keep going until there are none left
+/// 3) What's left joins the start to the goal; walk it
+/// In a perfect maze that's exactly the route. Loops in a braided maze are left behind, so take the shortest way through.
+impl MazeSolver for DeadEndFilling {
+    fn solve(&self, grid: &dyn Grid, start: &ICellStrong, goal: &ICellStrong, _rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) -> SolverRun {
+        let mut run = SolverRun::new(start);
+        let (start_key, goal_key) = (position(start), position(goal));
+        let mut filled: HashSet<(usize, usize)> = HashSet::new();
+
+        let open_links = |cell: &ICellStrong, filled: &HashSet<(usize, usize)>| -> Vec<ICellStrong> {
+            linked_neighbors(cell).into_iter()
+                .filter(|l| !filled.contains(&position(l)))
+                .collect()
+        };
+
+        let mut pending: Vec<ICellStrong> = grid.each_cell().iter()
+            .filter_map(|c| c.as_ref())
+            .map(|c| Rc::clone(c))
+            .collect();
+
+        while let Some(cell) = pending.pop() {
+            let key = position(&cell);
+            if key == start_key || key == goal_key || filled.contains(&key) {
+                continue;
+            }
+
+            let open = open_links(&cell, &filled);
+            if open.len() <= 1 {
+                filled.insert(key);
+                run.events.push(SolverEvent::Fill(key));
+                pending.extend(open);
+            }
+        }
+
+        // Breadth-first through what's left, which is a single corridor unless the maze has loops.
+        let mut came_from: HashMap<(usize, usize), ICellStrong> = HashMap::new();
+        let mut frontier = vec![Rc::clone(start)];
+        let mut seen: HashSet<(usize, usize)> = HashSet::new();
+        seen.insert(start_key);
+
+        while !frontier.is_empty() && !seen.contains(&goal_key) {
+            let mut next_frontier = vec![];
+            for cell in frontier.iter() {
+                for link in open_links(cell, &filled) {
+                    if seen.insert(position(&link)) {
+                        came_from.insert(position(&link), Rc::clone(cell));
+                        next_frontier.push(link);
+                    }
+                }
+            }
+            frontier = next_frontier;
+        }
+
+        if !seen.contains(&goal_key) {
+            return run;
+        }
+
+        let mut route = vec![Rc::clone(goal)];
+        while position(route.last().unwrap()) != start_key {
+            let previous = Rc::clone(&came_from[&position(route.last().unwrap())]);
+            route.push(previous);
+        }
+
+        for cell in route.iter().rev().skip(1) {
+            run.move_to(cell);
+        }
+
+        run.solved = true;
+        run
+    }
+}
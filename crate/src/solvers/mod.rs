@@ -0,0 +1,76 @@
+use crate::cells::ICellStrong;
+use crate::grid::Grid;
+use crate::rng::RngWrapper;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+pub mod wall_follower;
+pub mod tremaux;
+pub mod dead_end_filling;
+pub mod random_mouse;
+
+/// Something a solver did on its way through the maze. Cells are given as (row, column).
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolverEvent {
+    /// The solver moved into this cell.
+    Move((usize, usize)),
+    /// The cell was ruled out and filled in without being walked.
+    Fill((usize, usize)),
+}
+
+/// Everything a solver did, in order, so a run can be replayed and compared with others.
+#[derive(Debug, Clone)]
+pub struct SolverRun {
+    pub start: (usize, usize),
+    pub events: Vec<SolverEvent>,
+    pub solved: bool,
+}
+
+impl SolverRun {
+    fn new(start: &ICellStrong) -> SolverRun {
+        SolverRun { start: position(start), events: vec![], solved: false }
+    }
+
+    /// One step per recorded event: every move and every filled cell.
+    pub fn steps(&self) -> usize {
+        self.events.len()
+    }
+
+    /// The cells walked through, starting with `start`.
+    pub fn route(&self) -> Vec<(usize, usize)> {
+        let mut route = vec![self.start];
+        for event in self.events.iter() {
+            if let SolverEvent::Move(cell) = event {
+                route.push(*cell);
+            }
+        }
+        route
+    }
+
+    fn move_to(&mut self, cell: &ICellStrong) {
+        self.events.push(SolverEvent::Move(position(cell)));
+    }
+}
+
+pub trait MazeSolver: Debug {
+    fn solve(&self, grid: &dyn Grid, start: &ICellStrong, goal: &ICellStrong, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) -> SolverRun;
+}
+
+pub fn position(cell: &ICellStrong) -> (usize, usize) {
+    let c = cell.borrow();
+    (c.row(), c.column())
+}
+
+fn linked_neighbors(cell: &ICellStrong) -> Vec<ICellStrong> {
+    cell.borrow().links().iter()
+        .filter_map(|l| l.as_ref())
+        .map(|l| Rc::clone(l))
+        .collect()
+}
+
+/// Total number of links in the grid. No walk that never repeats itself can take more steps than this.
+fn link_count(grid: &dyn Grid) -> usize {
+    grid.each_cell().iter()
+        .filter_map(|c| c.as_ref())
+        .fold(0, |acc, c| acc + c.borrow().links().len())
+}
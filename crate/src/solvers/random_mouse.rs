@@ -0,0 +1,66 @@
+use crate::cells::ICellStrong;
+use crate::grid::Grid;
+use crate::rng::RngWrapper;
+use crate::algorithms::rand_element;
+use crate::solvers::{MazeSolver, SolverRun, position, linked_neighbors};
+use std::rc::Rc;
+
+#[derive(Debug)]
+pub struct RandomMouse {
+    max_steps: usize
+}
+
+impl RandomMouse {
+    /// The mouse gives up after `max_steps` moves.
+    pub fn new(max_steps: usize) -> RandomMouse {
+        RandomMouse { max_steps }
+    }
+}
+
+impl Default for RandomMouse {
+    fn default() -> RandomMouse {
+        RandomMouse::new(100_000)
+    }
+}
+
+/// Random Mouse
+/// Wander along a random passage, never turning back unless at a dead end.
+/// Always gets there eventually, but usually takes far longer than anything else.
+impl MazeSolver for RandomMouse {
+    fn solve(&self, _grid: &dyn Grid, start: &ICellStrong, goal: &ICellStrong, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) -> SolverRun {
+        let mut run = SolverRun::new(start);
+        let goal = position(goal);
+
+        let mut current = Rc::clone(start);
+        let mut came_from: Option<(usize, usize)> = None;
+
+        while position(&current) != goal {
+            if run.events.len() >= self.max_steps {
+                return run;
+            }
+
+            let links = linked_neighbors(&current);
+            if links.is_empty() {
+                return run;
+            }
+
+            let forward: Vec<ICellStrong> = links.iter()
+                .filter(|l| Some(position(l)) != came_from)
+                .map(|l| Rc::clone(l))
+                .collect();
+
+            let next = if forward.is_empty() {
+                Rc::clone(&links[0])
+            } else {
+                Rc::clone(rand_element(&forward, rng_generator))
+            };
+
+            came_from = Some(position(&current));
+            run.move_to(&next);
+            current = next;
+        }
+
+        run.solved = true;
+        run
+    }
+}
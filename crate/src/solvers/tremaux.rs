@@ -0,0 +1,76 @@
+use crate::cells::ICellStrong;
+use crate::grid::Grid;
+use crate::rng::RngWrapper;
+use crate::algorithms::rand_element;
+use crate::solvers::{MazeSolver, SolverRun, position, linked_neighbors, link_count};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+#[derive(Debug)]
+pub struct Tremaux;
+
+/// Trémaux's Algorithm
+/// Mark every passage each time you walk along it. No passage is ever walked more than twice.
+/// 1) At a cell you haven't seen before, take any unmarked passage
+/// 2) If you come back to a cell you've already seen along a passage marked once, turn around
+/// 3) Otherwise take the passage with the fewest marks
+/// Passages marked exactly once when the goal is found make up a route from the start.
+impl MazeSolver for Tremaux {
+    fn solve(&self, grid: &dyn Grid, start: &ICellStrong, goal: &ICellStrong, rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) -> SolverRun {
+        let mut run = SolverRun::new(start);
+        let goal = position(goal);
+        let max_steps = link_count(grid);
+        let mut marks: HashMap<((usize, usize), (usize, usize)), u32> = HashMap::new();
+
+        let mut current = Rc::clone(start);
+        let mut came_from: Option<ICellStrong> = None;
+
+        while position(&current) != goal {
+            if run.events.len() > max_steps {
+                return run;
+            }
+
+            let here = position(&current);
+            let links = linked_neighbors(&current);
+            let marks_to = |cell: &ICellStrong| *marks.get(&passage(here, position(cell))).unwrap_or(&0);
+
+            let seen_before = links.iter()
+                .filter(|l| came_from.as_ref().map_or(true, |from| position(l) != position(from)))
+                .any(|l| marks_to(l) > 0);
+
+            let next = match &came_from {
+                Some(from) if seen_before && marks_to(from) == 1 => Some(Rc::clone(from)),
+                _ => {
+                    let fewest = links.iter().map(|l| marks_to(l)).filter(|m| *m < 2).min();
+                    let options: Vec<ICellStrong> = links.iter()
+                        .filter(|l| Some(marks_to(l)) == fewest)
+                        .map(|l| Rc::clone(l))
+                        .collect();
+
+                    if options.is_empty() {
+                        None
+                    } else {
+                        Some(Rc::clone(rand_element(&options, rng_generator)))
+                    }
+                }
+            };
+
+            let next = match next {
+                Some(next) => next,
+                None => return run
+            };
+
+            *marks.entry(passage(here, position(&next))).or_insert(0) += 1;
+            run.move_to(&next);
+            came_from = Some(current);
+            current = next;
+        }
+
+        run.solved = true;
+        run
+    }
+}
+
+fn passage(a: (usize, usize), b: (usize, usize)) -> ((usize, usize), (usize, usize)) {
+    if a < b { (a, b) } else { (b, a) }
+}
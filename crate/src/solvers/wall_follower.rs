@@ -0,0 +1,107 @@
+use crate::cells::{self, ICell, ICellStrong, cell::Cell, hex_cell::HexCell, polar_cell::PolarCell, triangle_cell::TriangleCell, weave_cell::WeaveCell};
+use crate::grid::Grid;
+use crate::rng::RngWrapper;
+use crate::solvers::{MazeSolver, SolverRun, position, link_count};
+use std::rc::Weak;
+use std::cell::RefCell;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Hand {
+    Left,
+    Right
+}
+
+#[derive(Debug)]
+pub struct WallFollower {
+    hand: Hand
+}
+
+impl WallFollower {
+    pub fn new(hand: Hand) -> WallFollower {
+        WallFollower { hand }
+    }
+}
+
+impl Default for WallFollower {
+    fn default() -> WallFollower {
+        WallFollower::new(Hand::Right)
+    }
+}
+
+/// Wall Follower
+/// Keep one hand on the wall and walk until the goal turns up.
+/// At each cell, take the first open side found by sweeping from the side you came in through, towards your hand.
+/// Only finds the goal if it's on the same wall as the start, which is always the case in a perfect maze.
+/// Gives up once it has walked every passage in both directions without getting there.
+impl MazeSolver for WallFollower {
+    fn solve(&self, grid: &dyn Grid, start: &ICellStrong, goal: &ICellStrong, _rng_generator: &dyn RngWrapper<Shuffle=ICellStrong>) -> SolverRun {
+        let mut run = SolverRun::new(start);
+        let goal = position(goal);
+        let max_steps = link_count(grid);
+
+        let mut current = start.clone();
+        let mut came_from: Option<(usize, usize)> = None;
+
+        while position(&current) != goal {
+            if run.events.len() > max_steps {
+                return run;
+            }
+
+            let sides = sides(&current);
+            let entry = came_from
+                .and_then(|from| sides.iter().position(|s| s.as_ref().map_or(false, |s| position(s) == from)))
+                .unwrap_or(0);
+
+            let count = sides.len() as isize;
+            let direction = if self.hand == Hand::Right { -1 } else { 1 };
+            let next = (1..=count)
+                .map(|turn| ((entry as isize + direction * turn).rem_euclid(count)) as usize)
+                .filter_map(|i| sides[i].clone())
+                .find(|side| cells::is_linked(&current, side));
+
+            match next {
+                Some(next) => {
+                    came_from = Some(position(&current));
+                    run.move_to(&next);
+                    current = next;
+                },
+                None => return run
+            }
+        }
+
+        run.solved = true;
+        run
+    }
+}
+
+/// The cell's neighbors in clockwise order around its walls, with `None` where a wall faces the edge of the grid.
+pub fn sides(cell: &ICellStrong) -> Vec<Option<ICellStrong>> {
+    let c = cell.borrow();
+    let c = c.as_any();
+
+    if let Some(c) = c.downcast_ref::<Cell>() {
+        vec![upgrade(&c.north), upgrade(&c.east), upgrade(&c.south), upgrade(&c.west)]
+    } else if let Some(c) = c.downcast_ref::<WeaveCell>() {
+        vec![upgrade(&c.north), upgrade(&c.east), upgrade(&c.south), upgrade(&c.west)]
+    } else if let Some(c) = c.downcast_ref::<HexCell>() {
+        vec![upgrade(&c.north), upgrade(&c.northeast), upgrade(&c.southeast), upgrade(&c.south), upgrade(&c.southwest), upgrade(&c.northwest)]
+    } else if let Some(c) = c.downcast_ref::<TriangleCell>() {
+        if c.upright() {
+            vec![upgrade(&c.east), upgrade(&c.south), upgrade(&c.west)]
+        } else {
+            vec![upgrade(&c.north), upgrade(&c.east), upgrade(&c.west)]
+        }
+    } else if let Some(c) = c.downcast_ref::<PolarCell>() {
+        // Along the inner wall, out along the clockwise wall, back along the outer wall, then in again.
+        let mut sides = vec![upgrade(&c.inward), upgrade(&c.cw)];
+        sides.extend(c.outward.iter().rev().map(upgrade));
+        sides.push(upgrade(&c.ccw));
+        sides
+    } else {
+        cell.borrow().neighbors().into_iter().map(Some).collect()
+    }
+}
+
+fn upgrade<T: 'static + ICell>(cell: &Option<Weak<RefCell<T>>>) -> Option<ICellStrong> {
+    cell.as_ref().and_then(|c| c.upgrade()).map(|c| c as ICellStrong)
+}
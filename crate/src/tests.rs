@@ -1,10 +1,21 @@
 #[cfg(test)]
 mod tests {
-    use crate::algorithms::{aldous_broder::AldousBroder, wilson::Wilson, hunt_and_kill::HuntAndKill, recursive_backtracker::RecursiveBacktracker, MazeAlgorithm};
+    use crate::algorithms::{aldous_broder::AldousBroder, wilson::Wilson, hunt_and_kill::HuntAndKill, recursive_backtracker::RecursiveBacktracker, kruskal::{Kruskal, WeaveKruskal}, prims::{SimplifiedPrims, TruePrims, CostSource, RandomCosts}, MazeAlgorithm};
+    use crate::algorithms::{Corner, bias_neighbors, binary_tree::BinaryTree, sidewinder::Sidewinder};
+    use crate::algorithms::ellers::{Ellers, EllerRows};
+    use crate::algorithms::recursive_division::RecursiveDivision;
+    use crate::algorithms::houston::Houston;
+    use crate::algorithms::steps::{record_events, replay, GenerationEvent};
+    use crate::algorithms::growing_tree::{GrowingTree, CellSelector, Newest, Oldest, Random, Weighted};
     use crate::cells::{ICellStrong};
-    use crate::grid::standard_grid::StandardGrid;
-    use crate::grid::{Grid, CellFormatter, mask::Mask, masked_grid::MaskedGrid, distances::DistanceGrid,};
-    use crate::rng::{thread_rng};
+    use crate::analysis::{analyze, analyze_between, MazeStats};
+    use crate::save::{SavedMaze, GridKind, LoadError};
+    use crate::solvers::{MazeSolver, wall_follower::{WallFollower, Hand}, tremaux::Tremaux, dead_end_filling::DeadEndFilling, random_mouse::RandomMouse};
+    use crate::grid::{standard_grid::StandardGrid, polar_grid::PolarGrid, hex_grid::HexGrid, triangle_grid::TriangleGrid, weave_grid::WeaveGrid};
+    use crate::grid::parse::{parse_standard, parse_masked, ParseError};
+    use crate::grid::{raster::{RasterOptions, parse_color, BLACK, WHITE}, png::{crc32, adler32}, backend::{Recorder, Primitive}};
+    use crate::grid::{astar::{astar, estimate}, braid::dead_ends, Grid, GridType, CellFormatter, mask::Mask, masked_grid::MaskedGrid, grid_base::Sparsity, distances::{DistanceGrid, Distances, Weights}};
+    use crate::rng::{thread_rng, seeded_rng::SeededRng, RngWrapper};
     // use crate::test::Bencher;
     use std::fs;
 
@@ -30,38 +41,121 @@ mod tests {
         grid
     }
 
-//     #[test]
-//     fn binary_tree() {
-//         let grid = StandardGrid::new(5,5);
+    fn test_mask() -> Mask {
+        let mut mask = Mask::new(5, 5);
+        mask.set(0,2, false);
+        mask.set(1,2, false);
+        mask.set(2,2, false);
+        mask.set(0,0, false);
+        mask.set(2,0, false);
+        mask.set(3,0, false);
+        mask
+    }
 
-//         let thread_rng = thread_rng::ThreadRng;
-//         BinaryTree.on(&grid, &thread_rng);
+    fn all_grids() -> Vec<Box<dyn Grid>> {
+        vec![
+            Box::new(StandardGrid::new(6,6)),
+            Box::new(PolarGrid::new(6,6)),
+            Box::new(HexGrid::new(6,6)),
+            Box::new(TriangleGrid::new(6,6)),
+            Box::new(MaskedGrid::new(test_mask())),
+        ]
+    }
 
-//         // This prints the grid with Dijkstra's distances inside, rendered as characters a,b,c, etc. 
-//         // Will probably need to adjust for really large grids if I really want to display them with distances.
-//         // grabs first cell of first row
-//         let root = grid.cells().first().unwrap().first().unwrap();
-//         let last = grid.cells().last().unwrap().first().unwrap();
-//         let mut distance_grid = DistanceGrid::new(&root.as_ref().unwrap());
+    // `all_grids` with a weave grid last.
+    fn all_grids_with_weave() -> Vec<Box<dyn Grid>> {
+        let mut grids = all_grids();
+        grids.push(Box::new(WeaveGrid::new(6, 6)));
+        grids
+    }
+
+    // A perfect maze over n cells has exactly n - 1 passages.
+    fn assert_perfect(grid: &dyn Grid) {
+        let links = grid.each_cell().iter()
+            .filter_map(|c| c.as_ref())
+            .fold(0, |acc, c| acc + c.borrow().links().len());
+        assert_eq!(links / 2, grid.size() - 1);
+    }
+
+    #[test]
+    fn binary_tree() {
+        let grid = StandardGrid::new(5,5);
+
+        let thread_rng = thread_rng::ThreadRng;
+        BinaryTree::default().on(&grid, &thread_rng);
+
+        // This prints the grid with Dijkstra's distances inside, rendered as characters a,b,c, etc. 
+        // Will probably need to adjust for really large grids if I really want to display them with distances.
+        // grabs first cell of first row
+        let root = grid.cells().first().unwrap().first().unwrap();
+        let last = grid.cells().last().unwrap().first().unwrap();
+        let mut distance_grid = DistanceGrid::new(&root.as_ref().unwrap());
         
         
-//         // builds a path to the first cell of the last row
-//         distance_grid.build_path_to(&last.as_ref().unwrap());
-//         println!("{}", grid.to_string(&distance_grid));
-//         distance_grid.set_show_path_only(true);
+        // builds a path to the first cell of the last row
+        distance_grid.build_path_to(&last.as_ref().unwrap(), &grid);
+        println!("{}", grid.to_string(&distance_grid));
+        distance_grid.set_show_path_only(true);
         
-//         // shows the shortest path from root (NW) to SW corner as constructed above
-//         println!("{}", grid.to_string(&distance_grid));
+        // shows the shortest path from root (NW) to SW corner as constructed above
+        println!("{}", grid.to_string(&distance_grid));
+
+        // rebuilds path grid to determine and show the longest path
+        distance_grid.build_longest_path(&grid);
+        println!("{}", grid.to_string(&distance_grid));
+    }
+
+    #[test]
+    fn sidewinder() {
+        test_std_grid(Sidewinder::default());
+    }
+
+    #[test]
+    fn biased_algorithms_on_every_grid() {
+        let corners = [Corner::NorthEast, Corner::NorthWest, Corner::SouthEast, Corner::SouthWest];
+        for corner in corners.iter() {
+            for grid in all_grids().iter() {
+                BinaryTree::new(*corner).on(&**grid, &thread_rng::ThreadRng);
+                assert_perfect(&**grid);
+            }
 
-//         // rebuilds path grid to determine and show the longest path
-//         distance_grid.build_longest_path(&grid);
-//         println!("{}", grid.to_string(&distance_grid));
-//     }
+            for grid in all_grids().iter() {
+                Sidewinder::new(*corner).on(&**grid, &thread_rng::ThreadRng);
+                assert_perfect(&**grid);
+            }
+        }
 
-//     #[test]
-//     fn sidewinder() {
-//         test_std_grid(Sidewinder);
-//     }
+        // On every full grid the bias holds for every passage: each one runs towards the corner from one of its ends,
+        // so nothing was left over for random joining.
+        let towards_corner = |cell: &ICellStrong, other: &ICellStrong, corner: Corner| {
+            let (vertical, horizontal) = bias_neighbors(cell, corner);
+            vec![vertical, horizontal].iter().filter_map(|n| n.as_ref()).any(|n| *n.borrow() == *other.borrow())
+        };
+        for corner in corners.iter() {
+            let algorithms: Vec<Box<dyn MazeAlgorithm>> = vec![Box::new(BinaryTree::new(*corner)), Box::new(Sidewinder::new(*corner))];
+            for alg in algorithms.iter() {
+                for grid in all_grids().iter().take(4) {
+                    alg.on(&**grid, &thread_rng::ThreadRng);
+                    for cell in grid.each_cell().iter().filter_map(|c| c.as_ref()) {
+                        for link in cell.borrow().links().iter().filter_map(|l| l.as_ref()) {
+                            assert!(towards_corner(cell, link, *corner) || towards_corner(link, cell, *corner), "{:?} {:?}", alg, corner);
+                        }
+                    }
+                }
+            }
+        }
+
+        // With a north east bias, the top row and the east column are always open corridors.
+        let grid = StandardGrid::new(6, 6);
+        BinaryTree::new(Corner::NorthEast).on(&grid, &thread_rng::ThreadRng);
+        for column in 0..5 {
+            let cell = grid.get_cell(0, column).unwrap();
+            assert!(cell.borrow().links().iter().any(|l| {
+                let l = l.as_ref().unwrap().borrow();
+                l.row() == 0 && l.column() == column + 1
+            }));
+        }
+    }
 
     #[test]
     fn aldous_broder() {
@@ -83,7 +177,1038 @@ mod tests {
     fn recursive_backtracker() {
         test_std_grid(RecursiveBacktracker);
     }
-    
+
+    #[test]
+    fn kruskal() {
+        test_std_grid(Kruskal);
+
+        for grid in all_grids().iter() {
+            Kruskal.on(&**grid, &thread_rng::ThreadRng);
+            assert_perfect(&**grid);
+        }
+    }
+
+    #[test]
+    fn simplified_prims() {
+        test_std_grid(SimplifiedPrims);
+
+        for grid in all_grids().iter() {
+            SimplifiedPrims.on(&**grid, &thread_rng::ThreadRng);
+            assert_perfect(&**grid);
+        }
+    }
+
+    #[derive(Debug)]
+    struct ColumnCosts;
+    impl CostSource for ColumnCosts {
+        fn cost_of(&self, cell: &ICellStrong, _rng: &dyn RngWrapper<Shuffle=ICellStrong>) -> u32 {
+            cell.borrow().column() as u32
+        }
+    }
+
+    #[test]
+    fn true_prims() {
+        test_std_grid(TruePrims::default());
+
+        for grid in all_grids().iter() {
+            TruePrims::default().on(&**grid, &thread_rng::ThreadRng);
+            assert_perfect(&**grid);
+        }
+
+        // No range to pick costs from still carves a maze.
+        let grid = StandardGrid::new(5, 5);
+        TruePrims::new(Box::new(RandomCosts::new(0))).on(&grid, &thread_rng::ThreadRng);
+        assert_perfect(&grid);
+
+        // Cheaper cells are always taken first, so the first column is carved as one straight corridor.
+        let grid = StandardGrid::new(5, 5);
+        TruePrims::new(Box::new(ColumnCosts)).on(&grid, &thread_rng::ThreadRng);
+        for row in 0..4 {
+            let cell = grid.get_cell(row, 0).unwrap();
+            let south = grid.get_cell(row + 1, 0).unwrap();
+            assert!(cell.borrow().links().iter().any(|l| l.as_ref().unwrap().borrow().row() == south.borrow().row()
+                && l.as_ref().unwrap().borrow().column() == 0));
+        }
+    }
+
+    // Always works from the middle of the active list.
+    #[derive(Debug)]
+    struct Middle;
+    impl CellSelector for Middle {
+        fn select(&self, active: &[ICellStrong], _rng: &dyn RngWrapper<Shuffle=ICellStrong>) -> usize {
+            active.len() / 2
+        }
+    }
+
+    #[test]
+    fn growing_tree() {
+        let selectors: Vec<Box<dyn Fn() -> Box<dyn CellSelector>>> = vec![
+            Box::new(|| Box::new(Newest)),
+            Box::new(|| Box::new(Oldest)),
+            Box::new(|| Box::new(Random)),
+            Box::new(|| Box::new(Middle)),
+            Box::new(|| Box::new(Weighted::new(vec![(75, Box::new(Newest)), (25, Box::new(Random))]))),
+        ];
+
+        for selector in selectors.iter() {
+            test_std_grid(GrowingTree::new(selector()));
+
+            for grid in all_grids().iter() {
+                GrowingTree::new(selector()).on(&**grid, &thread_rng::ThreadRng);
+                assert_perfect(&**grid);
+            }
+        }
+    }
+
+    #[test]
+    fn ellers() {
+        test_std_grid(Ellers);
+
+        let grid = StandardGrid::new(4, 7);
+        Ellers.on(&grid, &thread_rng::ThreadRng);
+        assert_perfect(&grid);
+
+        // Voids are stepped around rather than unwrapped.
+        let grid = MaskedGrid::new(test_mask());
+        Ellers.on(&grid, &thread_rng::ThreadRng);
+        assert_perfect(&grid);
+
+        let mut grid = StandardGrid::new(6, 6);
+        RecursiveBacktracker.on(&grid, &thread_rng::ThreadRng);
+        grid.sparsify(Sparsity::Passes(2), &thread_rng::ThreadRng);
+        for cell in grid.each_cell().iter().filter_map(|c| c.as_ref()) {
+            let links = cell.borrow().links();
+            for link in links.iter().filter_map(|l| l.as_ref()) {
+                grid.unlink(cell, link);
+            }
+        }
+        Ellers.on(&grid, &thread_rng::ThreadRng);
+        assert_perfect(&grid);
+    }
+
+    #[test]
+    fn ellers_stream() {
+        let rng = thread_rng::ThreadRng;
+        let rows: Vec<_> = EllerRows::new(6, Some(4), &rng).collect();
+        assert_eq!(rows.len(), 4);
+        assert!(rows.last().unwrap().south.iter().all(|s| !s));
+        // A perfect maze over 24 cells has 23 passages.
+        let passages = rows.iter().fold(0, |acc, r| {
+            acc + r.east.iter().filter(|&&e| e).count() + r.south.iter().filter(|&&s| s).count()
+        });
+        assert_eq!(passages, 23);
+
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(row.row, i);
+            assert_eq!((row.east.len(), row.south.len()), (5, 6));
+            // Only the first row draws the top border.
+            let text = row.to_string();
+            assert_eq!(text.lines().count(), if i == 0 { 3 } else { 2 });
+            assert!(text.lines().all(|l| l.len() == 25));
+        }
+
+        // Unbounded streams keep going for as long as they are asked to.
+        let endless = EllerRows::new(10, None, &rng);
+        assert_eq!(endless.take(10_000).count(), 10_000);
+    }
+
+    #[test]
+    fn houston() {
+        test_std_grid(Houston::default());
+
+        for fraction in [0.0, 0.5, 1.0].iter() {
+            for grid in all_grids().iter() {
+                Houston::new(*fraction).on(&**grid, &thread_rng::ThreadRng);
+                assert_perfect(&**grid);
+            }
+        }
+    }
+
+    // Over and under cells together form a tree that reaches every cell of the grid.
+    fn assert_weave_perfect(grid: &WeaveGrid) {
+        assert_perfect(grid);
+
+        let cells: Vec<ICellStrong> = grid.each_cell().into_iter().filter_map(|c| c).collect();
+        let distances = Distances::new(&grid.get_cell(0, 0).unwrap(), true);
+        for cell in cells.iter() {
+            assert!(distances.is_visited(cell.borrow().row(), cell.borrow().column()));
+        }
+    }
+
+    #[test]
+    fn weave_recursive_backtracker() {
+        let grid = WeaveGrid::new(10, 10);
+        RecursiveBacktracker.on(&grid, &thread_rng::ThreadRng);
+        assert_weave_perfect(&grid);
+
+        // Under cells are found by their offset row.
+        for under in grid.under_cells.borrow().iter() {
+            let (row, column) = (under.borrow().row, under.borrow().column);
+            let found = grid.get_cell(row + grid.rows, column).unwrap();
+            assert_eq!(found.borrow().row(), row + grid.rows);
+            assert!(grid.get_cell(row, column).unwrap().borrow().links().len() == 2);
+        }
+    }
+
+    #[test]
+    fn weave_kruskal() {
+        let grid = WeaveGrid::new(10, 10);
+        WeaveKruskal.on(&grid, &thread_rng::ThreadRng);
+        assert_weave_perfect(&grid);
+        assert!(!grid.under_cells.borrow().is_empty());
+
+        // Plain Kruskal's runs on a weave grid too, it just never tunnels.
+        let grid = WeaveGrid::new(6, 6);
+        Kruskal.on(&grid, &thread_rng::ThreadRng);
+        assert_weave_perfect(&grid);
+    }
+
+    #[test]
+    fn weave_to_string() {
+        let grid = WeaveGrid::new(3, 3);
+        let cell = |row, column| grid.get_cell(row, column).unwrap();
+        grid.link(&cell(1, 0), &cell(1, 1));
+        grid.link(&cell(1, 1), &cell(1, 2));
+        // Tunnels under the middle cell.
+        grid.link(&cell(0, 1), &cell(2, 1));
+
+        assert_eq!(grid.size(), 10);
+        assert_eq!(grid.each_cell().len(), grid.size());
+        assert_eq!(grid.to_string(&ConsoleGridFormatter), concat!(
+            "\r+---+---+---+\r\n",
+            "|   |   |   |\r\n",
+            "+---+   +---+\r\n",
+            "|     -     |\r\n",
+            "+---+   +---+\r\n",
+            "|   |   |   |\r\n",
+            "+---+---+---+\r\n",
+        ));
+
+        // Contents take the place of the crossing mark.
+        let distances = DistanceGrid::new(&cell(1, 0));
+        assert_eq!(grid.to_string(&distances), concat!(
+            "\r+---+---+---+\r\n",
+            "|   |   |   |\r\n",
+            "+---+   +---+\r\n",
+            "| 0   1   2 |\r\n",
+            "+---+   +---+\r\n",
+            "|   |   |   |\r\n",
+            "+---+---+---+\r\n",
+        ));
+    }
+
+    #[test]
+    fn unlink() {
+        for grid in all_grids().iter() {
+            let cell = grid.random_cell(&thread_rng::ThreadRng).unwrap();
+            let neighbor = cell.borrow().neighbors()[0].clone();
+            cell.borrow_mut().link(neighbor.clone());
+            neighbor.borrow_mut().link(cell.clone());
+
+            cell.borrow_mut().unlink(neighbor.clone());
+            neighbor.borrow_mut().unlink(cell.clone());
+            assert!(cell.borrow().links().is_empty());
+            assert!(neighbor.borrow().links().is_empty());
+        }
+    }
+
+    #[test]
+    fn grid_links() {
+        for grid in all_grids().iter() {
+            let cell = grid.random_cell(&thread_rng::ThreadRng).unwrap();
+            let neighbor = cell.borrow().neighbors()[0].clone();
+
+            assert!(grid.link(&cell, &neighbor));
+            assert!(grid.is_linked(&cell, &neighbor));
+            assert!(grid.is_linked(&neighbor, &cell));
+
+            assert!(grid.unlink(&neighbor, &cell));
+            assert!(!grid.is_linked(&cell, &neighbor));
+            assert!(!grid.is_linked(&neighbor, &cell));
+            assert!(!grid.unlink(&cell, &neighbor));
+
+            // Cells that don't share a wall can't be linked.
+            let far = grid.each_cell().into_iter()
+                .filter_map(|c| c)
+                .find(|c| *c.borrow() != *cell.borrow() && !cell.borrow().neighbors().iter().any(|n| *n.borrow() == *c.borrow()))
+                .unwrap();
+            assert!(!grid.link(&cell, &far));
+            assert!(cell.borrow().links().is_empty());
+        }
+    }
+
+    #[test]
+    fn recursive_division() {
+        let grid = test_std_grid(RecursiveDivision::default());
+        assert_perfect(&grid);
+
+        // Rooms leave extra passages behind, but every cell is still reachable.
+        let grid = StandardGrid::new(12, 12);
+        RecursiveDivision::new(5).on(&grid, &thread_rng::ThreadRng);
+        let root = grid.get_cell(0, 0).unwrap();
+        let distances = Distances::new(&root, true);
+        for cell in grid.each_cell().iter() {
+            let cell = cell.as_ref().unwrap().borrow();
+            assert!(distances.is_visited(cell.row(), cell.column()));
+        }
+        println!("{}", grid.to_string(&ConsoleGridFormatter));
+
+        // Voids never leave part of a masked grid walled off.
+        for _ in 0..10 {
+            let grid = MaskedGrid::new(test_mask());
+            RecursiveDivision::default().on(&grid, &thread_rng::ThreadRng);
+            assert_perfect(&grid);
+        }
+
+        // It can only split along rows and columns, so other grids are turned away, on the page too.
+        let supported: Vec<bool> = all_grids().iter().map(|g| RecursiveDivision::supports(&**g)).collect();
+        assert_eq!(supported, vec![true, false, false, false, true]);
+        assert!(!RecursiveDivision::supports(&WeaveGrid::new(4, 4)));
+        assert!(crate::check_grid_type("recursive_division", &GridType::StandardGrid).is_ok());
+        for grid_type in [GridType::PolarGrid, GridType::HexGrid, GridType::TriangleGrid, GridType::WeaveGrid].iter() {
+            assert!(crate::check_grid_type("recursive_division", grid_type).is_err());
+            assert!(crate::check_grid_type("recursive_backtracker", grid_type).is_ok());
+        }
+    }
+
+    #[test]
+    fn parse_names() {
+        // Every algorithm on the page has a name, and a typo is an error rather than a different maze.
+        for name in ["basic_binary_tree", "sidewinder", "aldous_broder", "wilson", "hunt_and_kill", "recursive_backtracker", "kruskal",
+                "simplified_prims", "true_prims", "growing_tree", "recursive_division", "houston"].iter() {
+            assert!(crate::parse_algorithm(name).is_ok(), "{}", name);
+        }
+        assert!(crate::parse_algorithm("recursive_backtraker").is_err());
+        assert!(crate::parse_algorithm("").is_err());
+
+        assert_eq!(crate::parse_corner("ne"), Ok(Corner::NorthEast));
+        assert_eq!(crate::parse_corner("sw"), Ok(Corner::SouthWest));
+        assert!(crate::parse_corner("north").is_err());
+    }
+
+    #[test]
+    fn generation_steps() {
+        let algorithms: Vec<Box<dyn MazeAlgorithm>> = vec![
+            Box::new(BinaryTree::default()), Box::new(Sidewinder::default()), Box::new(AldousBroder), Box::new(Wilson),
+            Box::new(HuntAndKill), Box::new(RecursiveBacktracker), Box::new(Kruskal), Box::new(SimplifiedPrims),
+            Box::new(TruePrims::default()), Box::new(GrowingTree::new(Box::new(Newest))), Box::new(Ellers),
+            Box::new(Houston::default()), Box::new(RecursiveDivision::default()),
+        ];
+
+        // Replaying the links and unlinks gives back exactly the passages in the finished maze.
+        for alg in algorithms.iter() {
+            let grid = StandardGrid::new(5, 5);
+            let mut passages = 0i32;
+            for event in record_events(alg.as_ref(), &grid, &thread_rng::ThreadRng) {
+                match event {
+                    GenerationEvent::Link(..) => passages += 1,
+                    GenerationEvent::Unlink(..) => passages -= 1,
+                    _ => {}
+                }
+            }
+            assert_eq!(passages as usize, grid.size() - 1, "{:?}", alg);
+        }
+
+        // With only two cells there is one way to carve it, whichever cell the walk starts from.
+        let grid = StandardGrid::new(1, 2);
+        let events = record_events(&RecursiveBacktracker, &grid, &thread_rng::ThreadRng);
+        let first = match events[0] { GenerationEvent::Visit(first) => first, _ => panic!("should start with a visit") };
+        let second = (0, 1 - first.1);
+        assert_eq!(events, vec![
+            GenerationEvent::Visit(first),
+            GenerationEvent::Link(first, second),
+            GenerationEvent::Visit(second),
+            GenerationEvent::Backtrack(second),
+            GenerationEvent::Backtrack(first),
+        ]);
+
+        // Replaying onto a blank grid of the same shape carves the same maze, tunnels included.
+        for (grid, blank) in all_grids().iter().zip(all_grids().iter()) {
+            for event in record_events(&RecursiveBacktracker, &**grid, &thread_rng::ThreadRng).iter() {
+                replay(&**blank, event);
+            }
+            assert_eq!(SavedMaze::from_grid(&**blank), SavedMaze::from_grid(&**grid));
+        }
+
+        let (grid, blank) = (WeaveGrid::new(6, 6), WeaveGrid::new(6, 6));
+        for event in record_events(&WeaveKruskal, &grid, &thread_rng::ThreadRng).iter() {
+            replay(&blank, event);
+        }
+        assert!(!blank.under_cells.borrow().is_empty());
+        assert_eq!(SavedMaze::from_grid(&blank), SavedMaze::from_grid(&grid));
+    }
+
+    #[test]
+    fn seeded_rng() {
+        // Reference SplitMix64 output, so any change to the sequence (and every shared maze) gets noticed.
+        let rng = SeededRng::new(0);
+        assert_eq!(rng.next_u64(), 0xE220_A839_7B1D_CDAF);
+        assert_eq!(rng.next_u64(), 0x6E78_9E6A_A1B9_65F4);
+
+        let rng = SeededRng::new(12345);
+        for _ in 0..1000 {
+            let value = rng.gen_range(3, 10);
+            assert!(value >= 3 && value < 10);
+        }
+
+        let build = |seed: u64| {
+            let grid = StandardGrid::new(8, 8);
+            let rng = SeededRng::new(seed);
+            RecursiveBacktracker.on(&grid, &rng);
+            grid.to_string(&ConsoleGridFormatter)
+        };
+        assert_eq!(build(12345), build(12345));
+        assert_ne!(build(12345), build(54321));
+    }
+
+    #[test]
+    fn braid() {
+        let count_links = |grid: &dyn Grid| grid.each_cell().iter()
+            .filter_map(|c| c.as_ref())
+            .fold(0, |acc, c| acc + c.borrow().links().len());
+
+        for grid in all_grids().iter() {
+            RecursiveBacktracker.on(&**grid, &thread_rng::ThreadRng);
+            let links = count_links(&**grid);
+            grid.braid(0.0, &thread_rng::ThreadRng);
+            assert_eq!(count_links(&**grid), links);
+
+            // Only dead ends with nowhere else to go are left.
+            grid.braid(1.0, &thread_rng::ThreadRng);
+            for cell in dead_ends(&grid.each_cell()).iter() {
+                assert_eq!(cell.borrow().neighbors().len(), 1);
+            }
+        }
+
+        let grid = WeaveGrid::new(8, 8);
+        WeaveKruskal.on(&grid, &thread_rng::ThreadRng);
+        grid.braid(1.0, &thread_rng::ThreadRng);
+        assert!(dead_ends(&grid.each_cell()).is_empty());
+    }
+
+    fn assert_connected(grid: &dyn Grid) {
+        let root = grid.random_cell(&thread_rng::ThreadRng).unwrap();
+        let distances = Distances::new(&root, true);
+        for cell in grid.each_cell().iter().filter_map(|c| c.as_ref()) {
+            let c = cell.borrow();
+            assert!(distances.is_visited(c.row(), c.column()));
+            for neighbor in c.neighbors().iter() {
+                let n = neighbor.borrow();
+                assert!(grid.get_cell(n.row(), n.column()).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn sparsify() {
+        let mut grid = StandardGrid::new(10, 10);
+        RecursiveBacktracker.on(&grid, &thread_rng::ThreadRng);
+        let removed = grid.sparsify(Sparsity::Passes(3), &thread_rng::ThreadRng);
+        assert!(!removed.is_empty());
+        assert_eq!(grid.size(), 100 - removed.len());
+        for (row, column) in removed.iter() {
+            assert!(grid.get_cell(*row, *column).is_none());
+        }
+        assert_connected(&grid);
+        println!("{}", grid.to_string(&ConsoleGridFormatter));
+
+        let mut grid = StandardGrid::new(10, 10);
+        Wilson.on(&grid, &thread_rng::ThreadRng);
+        grid.sparsify(Sparsity::Remaining(0.4), &thread_rng::ThreadRng);
+        assert_eq!(grid.size(), 40);
+        assert_connected(&grid);
+
+        let mut grid = MaskedGrid::new(test_mask());
+        Kruskal.on(&grid, &thread_rng::ThreadRng);
+        let removed = grid.sparsify(Sparsity::Passes(1), &thread_rng::ThreadRng);
+        let cells = grid.each_cell().iter().filter(|c| c.is_some()).count();
+        assert_eq!(grid.size(), cells);
+        assert_eq!(cells, 19 - removed.len());
+        assert_connected(&grid);
+    }
+
+    #[test]
+    fn weighted_distances() {
+        // A 2x2 loop: both routes from the top left to the bottom right take two steps.
+        let grid = StandardGrid::new(2, 2);
+        let cell = |row, column| grid.get_cell(row, column).unwrap();
+        grid.link(&cell(0, 0), &cell(0, 1));
+        grid.link(&cell(0, 1), &cell(1, 1));
+        grid.link(&cell(0, 0), &cell(1, 0));
+        grid.link(&cell(1, 0), &cell(1, 1));
+
+        let mut weights = Weights::new();
+        weights.set(0, 1, 50);
+        let distances = Distances::weighted(&cell(0, 0), weights.clone());
+        assert_eq!(distances.get_distance(1, 0), Some(&1));
+        assert_eq!(distances.get_distance(1, 1), Some(&2));
+        assert_eq!(distances.get_distance(0, 1), Some(&50));
+
+        // The lava at (0, 1) is avoided.
+        let path = distances.path_to(&cell(1, 1), &grid);
+        assert!(path.is_visited(1, 0));
+        assert!(!path.is_visited(0, 1));
+
+        // Colored by cost, so the lava is the darkest cell.
+        let distance_grid = DistanceGrid::weighted(&cell(0, 0), weights);
+        assert_eq!(distance_grid.background_color(&cell(0, 1)), "rgb(0,0,128)");
+        assert_eq!(distance_grid.background_color(&cell(0, 0)), "rgb(255,255,255)");
+
+        // A free corridor still costs a step per cell, so the way back always leads to the root.
+        let grid = StandardGrid::new(1, 4);
+        let cell = |row, column| grid.get_cell(row, column).unwrap();
+        let mut weights = Weights::new();
+        for column in 0..3 {
+            grid.link(&cell(0, column), &cell(0, column + 1));
+            weights.set(0, column + 1, 0);
+        }
+        assert_eq!(weights.get(0, 1), 1);
+        let distances = Distances::weighted(&cell(0, 0), weights);
+        assert_eq!(distances.get_distance(0, 3), Some(&3));
+        let path = distances.path_to(&cell(0, 3), &grid);
+        assert!((0..4).all(|column| path.is_visited(0, column)));
+    }
+
+    #[test]
+    fn astar_paths() {
+        let grids = all_grids_with_weave();
+
+        for grid in grids.iter() {
+            RecursiveBacktracker.on(&**grid, &thread_rng::ThreadRng);
+            grid.braid(0.5, &thread_rng::ThreadRng);
+
+            for _ in 0..10 {
+                let start = grid.random_cell(&thread_rng::ThreadRng).unwrap();
+                let goal = grid.random_cell(&thread_rng::ThreadRng).unwrap();
+                let (goal_row, goal_column) = (goal.borrow().row(), goal.borrow().column());
+
+                // Same length as a full flood from the start, and as many cells as path_to finds.
+                let distances = Distances::new(&start, true);
+                let path = astar(&**grid, &start, &goal).unwrap();
+                assert_eq!(path.get_distance(goal_row, goal_column), distances.get_distance(goal_row, goal_column));
+
+                let expected = distances.path_to(&goal, &**grid);
+                let path_length = grid.each_cell().iter()
+                    .filter_map(|c| c.as_ref())
+                    .filter(|c| path.is_visited(c.borrow().row(), c.borrow().column()))
+                    .count();
+                let expected_length = grid.each_cell().iter()
+                    .filter_map(|c| c.as_ref())
+                    .filter(|c| expected.is_visited(c.borrow().row(), c.borrow().column()))
+                    .count();
+                assert_eq!(path_length, expected_length);
+
+                // Never overestimates.
+                assert!(estimate(&**grid, &start, &goal) <= *path.get_distance(goal_row, goal_column).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn solvers() {
+        let solvers: Vec<Box<dyn MazeSolver>> = vec![
+            Box::new(WallFollower::new(Hand::Left)),
+            Box::new(WallFollower::new(Hand::Right)),
+            Box::new(Tremaux),
+            Box::new(DeadEndFilling),
+            Box::new(RandomMouse::default()),
+        ];
+
+        let grids = all_grids_with_weave();
+
+        for grid in grids.iter() {
+            RecursiveBacktracker.on(&**grid, &thread_rng::ThreadRng);
+            let start = grid.random_cell(&thread_rng::ThreadRng).unwrap();
+            let goal = grid.random_cell(&thread_rng::ThreadRng).unwrap();
+            let (goal_row, goal_column) = (goal.borrow().row(), goal.borrow().column());
+            let shortest = *Distances::new(&start, true).get_distance(goal_row, goal_column).unwrap() as usize;
+
+            for solver in solvers.iter() {
+                let run = solver.solve(&**grid, &start, &goal, &thread_rng::ThreadRng);
+                assert!(run.solved, "{:?}", solver);
+                assert!(run.steps() >= shortest);
+
+                // Every move goes along a passage and the walk ends at the goal.
+                let route = run.route();
+                assert_eq!(*route.last().unwrap(), (goal_row, goal_column));
+                for pair in route.windows(2) {
+                    let from = grid.get_cell(pair[0].0, pair[0].1).unwrap();
+                    let to = grid.get_cell(pair[1].0, pair[1].1).unwrap();
+                    assert!(grid.is_linked(&from, &to));
+                }
+            }
+
+            // Dead-end filling leaves only the route itself to walk.
+            let run = DeadEndFilling.solve(&**grid, &start, &goal, &thread_rng::ThreadRng);
+            assert_eq!(run.route().len() - 1, shortest);
+        }
+
+        // With the start walled in, there's nowhere to go.
+        let grid = StandardGrid::new(3, 3);
+        let start = grid.get_cell(0, 0).unwrap();
+        let goal = grid.get_cell(2, 2).unwrap();
+        for solver in solvers.iter() {
+            assert!(!solver.solve(&grid, &start, &goal, &thread_rng::ThreadRng).solved);
+        }
+    }
+
+    #[test]
+    fn analysis() {
+        // A straight corridor: two dead ends joined by one run.
+        let grid = StandardGrid::new(1, 3);
+        let cells: Vec<ICellStrong> = grid.each_cell().into_iter().filter_map(|c| c).collect();
+        grid.link(&cells[0], &cells[1]);
+        grid.link(&cells[1], &cells[2]);
+        let stats = analyze(&grid);
+        assert_eq!(stats.dead_ends, 2);
+        assert_eq!(stats.solution_length, Some(2));
+        assert_eq!(stats.diameter, 2);
+        assert_eq!(stats.average_corridor_length, 2.0);
+        assert_eq!((stats.turns, stats.loops, stats.junctions), (0, 0, 0));
+
+        // A U shape turns once on the way from corner to corner, closing it makes a loop.
+        let grid = StandardGrid::new(2, 2);
+        let cell = |row, column| grid.get_cell(row, column).unwrap();
+        grid.link(&cell(0, 0), &cell(1, 0));
+        grid.link(&cell(1, 0), &cell(1, 1));
+        grid.link(&cell(1, 1), &cell(0, 1));
+        let stats = analyze_between(&grid, &cell(0, 0), &cell(1, 1));
+        assert_eq!((stats.solution_length, stats.turns, stats.loops), (Some(2), 1, 0));
+        assert_eq!(stats.river_factor, 0.5);
+
+        grid.link(&cell(0, 1), &cell(0, 0));
+        let stats = analyze(&grid);
+        assert_eq!((stats.dead_ends, stats.loops), (0, 1));
+
+        // Cells that can't reach each other have no solution.
+        let stats = analyze(&StandardGrid::new(2, 2));
+        assert_eq!((stats.solution_length, stats.loops), (None, 0));
+
+        // Nothing to analyze, whether the grid has no rows or every cell is masked off.
+        let mut mask = Mask::new(2, 2);
+        for (row, column) in [(0, 0), (0, 1), (1, 0), (1, 1)].iter() {
+            mask.set(*row, *column, false);
+        }
+        assert_eq!(analyze(&StandardGrid::new(0, 0)), MazeStats::default());
+        assert_eq!(analyze(&MaskedGrid::new(mask)), MazeStats::default());
+
+        let grids = all_grids_with_weave();
+
+        for grid in grids.iter() {
+            RecursiveBacktracker.on(&**grid, &thread_rng::ThreadRng);
+            let stats = analyze(&**grid);
+            assert_eq!(stats.loops, 0);
+            assert!(stats.dead_end_ratio > 0.0 && stats.dead_end_ratio <= 1.0);
+            assert!(stats.branching_factor >= 0.0 && stats.branching_factor < 1.0);
+            assert!(stats.average_corridor_length >= 1.0);
+            assert!(stats.turns < stats.solution_length.unwrap().max(1));
+
+            let all: Vec<ICellStrong> = grid.each_cell().into_iter().filter_map(|c| c).collect();
+            let (exit_row, exit_column) = (all[all.len() - 1].borrow().row(), all[all.len() - 1].borrow().column());
+            let distance = *Distances::new(&all[0], true).get_distance(exit_row, exit_column).unwrap() as usize;
+            assert_eq!(stats.solution_length, Some(distance));
+            assert!(stats.diameter >= distance);
+
+            // Braiding only ever adds loops. A masked maze can have every dead end tucked into a pocket
+            // of voids with nothing to join it to, and then there are none to add.
+            let braidable = dead_ends(&grid.each_cell()).iter().any(|c| c.borrow().neighbors().len() > 1);
+            grid.braid(1.0, &thread_rng::ThreadRng);
+            let braided = analyze(&**grid);
+            assert_eq!(braided.loops > 0, braidable);
+            assert_eq!(braided.dead_ends < stats.dead_ends, braidable);
+        }
+    }
+
+    #[test]
+    fn save_and_load() {
+        let grids = all_grids_with_weave();
+
+        for (i, grid) in grids.iter().enumerate() {
+            if i == grids.len() - 1 {
+                WeaveKruskal.on(&**grid, &thread_rng::ThreadRng);
+            } else {
+                RecursiveBacktracker.on(&**grid, &thread_rng::ThreadRng);
+                grid.braid(0.5, &thread_rng::ThreadRng);
+            }
+
+            let key = |cell: &ICellStrong| (cell.borrow().row(), cell.borrow().column());
+            let mut saved = SavedMaze::from_grid(&**grid);
+            saved.start = grid.cells()[0].iter().flatten().next().map(key);
+            saved.goal = grid.cells().last().unwrap().iter().flatten().last().map(key);
+
+            for loaded in [SavedMaze::from_json(&saved.to_json()), SavedMaze::from_bytes(&saved.to_bytes())].iter() {
+                let loaded = loaded.as_ref().unwrap();
+                assert_eq!(*loaded, saved);
+
+                // The rebuilt grid has every passage of the original, tunnels included.
+                let rebuilt = loaded.to_grid().unwrap();
+                let mut resaved = SavedMaze::from_grid(&*rebuilt);
+                resaved.start = saved.start;
+                resaved.goal = saved.goal;
+                assert_eq!(resaved, saved);
+                assert_eq!(rebuilt.size(), grid.size());
+            }
+        }
+
+        // Cells cut out of a standard grid come back as a mask.
+        let mut grid = StandardGrid::new(6, 6);
+        RecursiveBacktracker.on(&grid, &thread_rng::ThreadRng);
+        grid.sparsify(Sparsity::Passes(1), &thread_rng::ThreadRng);
+        let saved = SavedMaze::from_grid(&grid);
+        assert_eq!(saved.kind, GridKind::Masked);
+        let rebuilt = saved.to_grid().unwrap();
+        assert_eq!(rebuilt.size(), grid.size());
+        assert_eq!(SavedMaze::from_grid(&*rebuilt), saved);
+
+        let json = r#"{"version": 1, "grid": "standard", "rows": 2, "columns": 2, "links": [[0, 0, 0, 1]]}"#;
+        let saved = SavedMaze::from_json(json).unwrap();
+        assert_eq!((saved.mask.clone(), saved.start), (None, None));
+        let grid = saved.to_grid().unwrap();
+        assert!(grid.is_linked(&grid.get_cell(0, 0).unwrap(), &grid.get_cell(0, 1).unwrap()));
+
+        let load = |json: &str| SavedMaze::from_json(json).and_then(|s| s.to_grid().map(|_| ()));
+        assert_eq!(load(&json.replace("\"version\": 1", "\"version\": 2")), Err(LoadError::UnsupportedVersion(2)));
+        assert_eq!(load(&json.replace("standard", "cube")), Err(LoadError::UnknownGrid(String::from("cube"))));
+        assert_eq!(load(&json.replace("\"rows\": 2, ", "")), Err(LoadError::MissingField("rows")));
+        assert_eq!(load(&json.replace("0, 0, 0, 1", "0, 0, 1, 1")), Err(LoadError::InvalidLink((0, 0), (1, 1))));
+        assert_eq!(load(&json.replace("0, 0, 0, 1", "0, 0, 0, 2")), Err(LoadError::NoSuchCell(0, 2)));
+
+        // Polar grids wrap columns around each ring, and masked grids have voids, but neither makes a cell.
+        let polar = r#"{"version": 1, "grid": "polar", "rows": 2, "columns": 2, "links": [[1, 0, 1, 1]], "start": [1, 0]}"#;
+        assert_eq!(load(polar), Ok(()));
+        assert_eq!(load(&polar.replace("1, 0, 1, 1", "1, 0, 1, 8")), Err(LoadError::NoSuchCell(1, 8)));
+        assert_eq!(load(&polar.replace("[1, 0]", "[1, 8]")), Err(LoadError::NoSuchCell(1, 8)));
+        let masked = r#"{"version": 1, "grid": "masked", "rows": 2, "columns": 2, "mask": ["X.", ".."], "links": [[0, 1, 1, 1]]}"#;
+        assert_eq!(load(masked), Ok(()));
+        assert_eq!(load(&masked.replace("0, 1, 1, 1", "0, 0, 1, 0")), Err(LoadError::NoSuchCell(0, 0)));
+        assert_eq!(load(&masked.replace("]]}", "]], \"goal\": [0, 0]}")), Err(LoadError::NoSuchCell(0, 0)));
+
+        let malformed_at = |result: Result<(), LoadError>| match result {
+            Err(LoadError::Malformed { position, .. }) => position,
+            other => panic!("{:?}", other)
+        };
+        assert_eq!(malformed_at(load(&json.replace("\"grid\":", "\"grid\""))), 22);
+
+        // Values of the wrong shape are pointed at too.
+        let bad = json.replace("[0, 0, 0, 1]", "[0, 0, 1]");
+        assert_eq!(malformed_at(load(&bad)), bad.find("[0, 0, 1]").unwrap());
+        let bad = json.replace("\"standard\"", "7");
+        assert_eq!(malformed_at(load(&bad)), bad.find("7").unwrap());
+
+        // Deep nesting is an error, not a stack overflow.
+        let deep = json.replace("[[0, 0, 0, 1]]", &"[".repeat(1_000_000));
+        assert_eq!(malformed_at(load(&deep)), deep.find('[').unwrap() + 31);
+
+        // Huge sizes are turned away before anything is allocated for them.
+        let huge = json.replace("\"rows\": 2", "\"rows\": 4000000000");
+        assert_eq!(malformed_at(load(&huge)), huge.find("4000000000").unwrap());
+        let mut big = saved.clone();
+        big.columns = crate::save::MAX_SIDE + 1;
+        assert_eq!(malformed_at(big.to_grid().map(|_| ())), 0);
+        assert_eq!(malformed_at(SavedMaze::from_bytes(&big.to_bytes()).map(|_| ())), 6);
+        big.columns = crate::save::MAX_SIDE;
+        assert!(SavedMaze::from_bytes(&big.to_bytes()).is_ok());
+
+        assert!(SavedMaze::from_bytes(b"MAZ").is_err());
+        let bytes = saved.to_bytes();
+        assert!(SavedMaze::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn parse_text() {
+        let grid = StandardGrid::new(4, 7);
+        RecursiveBacktracker.on(&grid, &thread_rng::ThreadRng);
+        grid.braid(0.5, &thread_rng::ThreadRng);
+        let text = grid.to_string(&ConsoleGridFormatter);
+        let parsed = parse_standard(&text).unwrap();
+        assert_eq!(parsed.to_string(&ConsoleGridFormatter), text);
+        assert_eq!(SavedMaze::from_grid(&parsed), SavedMaze::from_grid(&grid));
+
+        // Hand-drawn, with an entrance, an exit, labels, blank lines and no trailing spaces.
+        let text = "
++   +---+---+
+| S     | X |
++   +---+---+
+|         E
++---+---+---+
+";
+        let grid = parse_masked(text).unwrap();
+        assert_eq!(grid.size(), 5);
+        assert!(grid.get_cell(0, 2).is_none());
+        assert_connected(&grid);
+        assert_eq!(parse_standard(text).err(), Some(ParseError { line: 3, column: 10, message: String::from("masked-out cell in a standard grid") }));
+
+        let error = |text: &str| {
+            let e = parse_masked(text).err().unwrap();
+            (e.line, e.column)
+        };
+        assert_eq!(error("+---+\n|   |\n"), (3, 1));
+        assert_eq!(error("+---+\n|   *\n+---+"), (2, 5));
+        assert_eq!(error("+---+---+\n|   |   |\n+-- +---+"), (3, 2));
+        assert_eq!(error("+---+---+\n|     X |\n+---+---+"), (2, 5));
+        assert_eq!(error("+---+\n|   |  x\n+---+"), (2, 6));
+        assert_eq!(error("\n\n"), (1, 1));
+    }
+
+    #[test]
+    fn svg() {
+        let grids = all_grids_with_weave();
+
+        for grid in grids.iter() {
+            RecursiveBacktracker.on(&**grid, &thread_rng::ThreadRng);
+            let root = grid.each_cell().into_iter().filter_map(|c| c).next().unwrap();
+            let distances = DistanceGrid::new(&root);
+
+            let svg = grid.to_svg(&distances, true);
+            assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+            assert!(svg.ends_with("</svg>\n"));
+            assert!(svg.contains("<line"));
+            assert!(svg.contains("fill=\"rgb("));
+
+            // Uncolored, only walls are drawn.
+            let plain = grid.to_svg(&distances, false);
+            assert!(!plain.contains("rgb("));
+            assert!(plain.len() < svg.len());
+        }
+
+        // Each colored cell is filled once, in the formatter's color.
+        let grid = StandardGrid::new(3, 4);
+        RecursiveBacktracker.on(&grid, &thread_rng::ThreadRng);
+        let svg = grid.to_svg(&DistanceGrid::new(&grid.get_cell(0, 0).unwrap()), true);
+        assert_eq!(svg.matches("<polygon").count(), 12);
+        assert!(svg.contains("fill=\"rgb(255,255,255)\""));
+
+        // A single closed cell is just its four walls.
+        let grid = StandardGrid::new(1, 1);
+        let svg = grid.to_svg(&ConsoleGridFormatter, true);
+        assert_eq!(svg.matches("<line").count(), 4);
+        assert!(svg.contains("<line x1=\"0\" y1=\"0\" x2=\"15\" y2=\"0\"/>"));
+        assert!(!svg.contains("<polygon"));
+    }
+
+    #[test]
+    fn raster() {
+        let grids = all_grids_with_weave();
+        let options = RasterOptions { cell_size: 10.0, wall_width: 2.0, ..RasterOptions::default() };
+
+        for grid in grids.iter() {
+            RecursiveBacktracker.on(&**grid, &thread_rng::ThreadRng);
+            let root = grid.each_cell().into_iter().filter_map(|c| c).next().unwrap();
+            let distances = DistanceGrid::new(&root);
+
+            let raster = grid.to_raster(&distances, &options);
+            assert_eq!(raster.pixels.len(), raster.width * raster.height * 4);
+            assert_eq!(raster.pixel(0, 0), WHITE);
+            assert!(raster.pixels.chunks(4).any(|p| p == BLACK));
+            assert!(raster.pixels.chunks(4).any(|p| p != BLACK && p != WHITE));
+
+            // Uncolored, only walls are drawn.
+            let plain = grid.to_raster(&distances, &RasterOptions { colorize: false, ..options.clone() });
+            assert!(plain.pixels.chunks(4).all(|p| p == BLACK || p == WHITE));
+        }
+
+        // A 10 pixel cell with 2 pixel walls, in a 2 pixel margin.
+        let grid = StandardGrid::new(1, 2);
+        grid.link(&grid.get_cell(0, 0).unwrap(), &grid.get_cell(0, 1).unwrap());
+        let far = grid.get_cell(0, 1).unwrap();
+        let distances = DistanceGrid::new(&grid.get_cell(0, 0).unwrap());
+        let raster = grid.to_raster(&distances, &options);
+        assert_eq!((raster.width, raster.height), (24, 14));
+        assert_eq!(raster.pixel(7, 1), BLACK);
+        assert_eq!(raster.pixel(7, 2), BLACK);
+        assert_eq!(raster.pixel(7, 3), raster.pixel(7, 7));
+        assert_eq!(raster.pixel(1, 7), BLACK);
+        assert_eq!(raster.pixel(0, 7), WHITE);
+        // No wall between the two cells.
+        assert!((9..15).all(|x| raster.pixel(x, 7) != BLACK));
+        assert_eq!(Some(raster.pixel(17, 7)), parse_color(&distances.background_color(&far)));
+        assert_ne!(raster.pixel(17, 7), raster.pixel(7, 7));
+
+        // Thicker walls cover more of the cell.
+        let thick = grid.to_raster(&distances, &RasterOptions { wall_width: 4.0, ..options.clone() });
+        assert_eq!((thick.width, thick.height), (26, 16));
+        assert_eq!(thick.pixel(8, 4), BLACK);
+        assert_ne!(thick.pixel(8, 5), BLACK);
+
+        assert_eq!(parse_color("rgb(12, 34, 255)"), Some([12, 34, 255, 255]));
+        assert_eq!(parse_color("#0c22ff"), Some([12, 34, 255, 255]));
+        assert_eq!(parse_color(""), None);
+        assert_eq!(parse_color("rgb(1,2)"), None);
+    }
+
+    #[test]
+    fn png() {
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+
+        let grid = StandardGrid::new(20, 20);
+        RecursiveBacktracker.on(&grid, &thread_rng::ThreadRng);
+        let raster = grid.to_raster(&ConsoleGridFormatter, &RasterOptions::default());
+        let png = raster.to_png();
+
+        assert_eq!(&png[..8], &[137, 80, 78, 71, 13, 10, 26, 10]);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..20], &(raster.width as u32).to_be_bytes());
+        assert_eq!(&png[20..24], &(raster.height as u32).to_be_bytes());
+        assert_eq!(&png[24..29], &[8, 6, 0, 0, 0]);
+        assert_eq!(&png[29..33], &crc32(&png[12..29]).to_be_bytes());
+        assert_eq!(&png[png.len() - 12..], &[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]);
+
+        // Mostly runs of white and black, so it should compress well.
+        assert!(png.len() < raster.pixels.len() / 10);
+    }
+
+    #[test]
+    fn text_rendering() {
+        struct Marker;
+        impl CellFormatter for Marker {
+            fn contents_of(&self, _cell: &ICellStrong) -> String {
+                String::from("o")
+            }
+
+            fn background_color(&self, _cell: &ICellStrong) -> String {
+                String::from("")
+            }
+        }
+
+        // Every cell shows its contents, whatever the shape of the grid.
+        let grids: Vec<Box<dyn Grid>> = vec![Box::new(PolarGrid::new(6, 6)), Box::new(HexGrid::new(6, 6)), Box::new(TriangleGrid::new(6, 6))];
+        for grid in grids.iter() {
+            RecursiveBacktracker.on(&**grid, &thread_rng::ThreadRng);
+            let text = grid.to_string(&Marker);
+            assert_eq!(text.matches('o').count(), grid.size());
+            assert!(!grid.to_string(&ConsoleGridFormatter).contains('o'));
+        }
+
+        let grid = PolarGrid::new(2, 2);
+        grid.link(&grid.get_cell(0, 0).unwrap(), &grid.get_cell(1, 0).unwrap());
+        grid.link(&grid.get_cell(1, 0).unwrap(), &grid.get_cell(1, 1).unwrap());
+        let distances = DistanceGrid::new(&grid.get_cell(0, 0).unwrap());
+        assert_eq!(grid.to_string(&distances), concat!(
+            "+---------------------------+\n",
+            "|             0             |\n",
+            "+   +---+---+---+---+---+---+\n",
+            "| 1   2 |   |   |   |   |   |\n",
+            "+---+---+---+---+---+---+---+\n",
+        ));
+
+        // The wall between the last and first cells of a ring shows at both ends.
+        grid.link(&grid.get_cell(1, 6).unwrap(), &grid.get_cell(1, 0).unwrap());
+        let distances = DistanceGrid::new(&grid.get_cell(0, 0).unwrap());
+        assert!(grid.to_string(&distances).contains("\n  1   2 |   |   |   |   | 2\n"));
+
+        let grid = HexGrid::new(1, 2);
+        grid.link(&grid.get_cell(0, 0).unwrap(), &grid.get_cell(0, 1).unwrap());
+        assert_eq!(grid.to_string(&DistanceGrid::new(&grid.get_cell(0, 0).unwrap())), concat!(
+            " ___\n",
+            "/ 0 \\___\n",
+            "\\___  1 \\\n",
+            "    \\___/\n",
+        ));
+
+        let grid = TriangleGrid::new(1, 2);
+        grid.link(&grid.get_cell(0, 0).unwrap(), &grid.get_cell(0, 1).unwrap());
+        assert_eq!(grid.to_string(&DistanceGrid::new(&grid.get_cell(0, 0).unwrap())), concat!(
+            "    ____\n",
+            "  /     /\n",
+            " /0  1 /\n",
+            "/____ /\n",
+        ));
+    }
+
+    #[test]
+    fn unicode() {
+        let grid = StandardGrid::new(2, 2);
+        let cell = |i, j| grid.get_cell(i, j).unwrap();
+        grid.link(&cell(0, 0), &cell(0, 1));
+        grid.link(&cell(0, 0), &cell(1, 0));
+        grid.link(&cell(1, 0), &cell(1, 1));
+        let distances = DistanceGrid::new(&cell(0, 0));
+        assert_eq!(grid.to_unicode(&distances, false), concat!(
+            "┌───────┐\n",
+            "│ 0   1 │\n",
+            "│   ╶───┤\n",
+            "│ 1   2 │\n",
+            "└───────┘\n",
+        ));
+
+        // Masked-out cells are left blank, walled off from their neighbors.
+        let mut mask = Mask::new(2, 2);
+        mask.set(1, 1, false);
+        let grid = MaskedGrid::new(mask);
+        let cell = |i, j| grid.get_cell(i, j).unwrap();
+        grid.link(&cell(0, 0), &cell(0, 1));
+        grid.link(&cell(0, 0), &cell(1, 0));
+        assert_eq!(grid.to_unicode(&DistanceGrid::new(&cell(0, 0)), true), concat!(
+            "┌───────┐\n",
+            "│ 0   1 │\n",
+            "│   ┌───┘\n",
+            "│ 1 │\n",
+            "└───┘\n",
+        ).replace(" 0 ", "\x1b[48;2;255;255;255m\x1b[38;2;0;0;0m 0 \x1b[0m")
+         .replace(" 1 ", "\x1b[48;2;0;0;128m\x1b[38;2;255;255;255m 1 \x1b[0m"));
+
+        // Colors only show when asked for, and when the formatter has them.
+        let grid = StandardGrid::new(6, 6);
+        RecursiveBacktracker.on(&grid, &thread_rng::ThreadRng);
+        let distances = DistanceGrid::new(&grid.get_cell(0, 0).unwrap());
+        assert_eq!(grid.to_unicode(&distances, true).matches("\x1b[48;2;").count(), 36);
+        assert!(!grid.to_unicode(&distances, false).contains('\x1b'));
+        assert!(!grid.to_unicode(&ConsoleGridFormatter, true).contains('\x1b'));
+    }
+
+    #[test]
+    fn backend() {
+        let grids = all_grids_with_weave();
+
+        for grid in grids.iter() {
+            RecursiveBacktracker.on(&**grid, &thread_rng::ThreadRng);
+            let distances = DistanceGrid::new(&grid.each_cell().into_iter().filter_map(|c| c).next().unwrap());
+            let mut recorder = Recorder::new();
+            grid.draw(&mut recorder, &distances, true, 10.0);
+
+            assert!(recorder.size.is_some());
+            // Weave grids only fill the cells on top.
+            assert_eq!(recorder.fills().len(), grid.cells().iter().flatten().filter(|c| c.is_some()).count());
+            // Backgrounds all go down before any walls, straight or curved.
+            let fills = recorder.fills();
+            assert_eq!(recorder.primitives[..fills.len()].iter().collect::<Vec<&Primitive>>(), fills);
+
+            // The SVG is drawn from the same primitives.
+            let svg = grid.to_svg(&distances, true);
+            let mut scaled = Recorder::new();
+            grid.draw(&mut scaled, &distances, true, grid.cell_size());
+            assert_eq!(svg.matches("<line").count(), scaled.lines().len());
+
+            let mut plain = Recorder::new();
+            grid.draw(&mut plain, &distances, false, 10.0);
+            assert!(plain.fills().is_empty());
+            assert_eq!(plain.primitives.len(), recorder.primitives.len() - recorder.fills().len());
+        }
+
+        let grid = StandardGrid::new(1, 2);
+        grid.link(&grid.get_cell(0, 0).unwrap(), &grid.get_cell(0, 1).unwrap());
+        let mut recorder = Recorder::new();
+        grid.draw(&mut recorder, &ConsoleGridFormatter, false, 10.0);
+        assert_eq!(recorder.size, Some((20.0, 10.0)));
+        assert_eq!(recorder.primitives, vec![
+            Primitive::Line { from: (0.0, 0.0), to: (10.0, 0.0) },
+            Primitive::Line { from: (0.0, 0.0), to: (0.0, 10.0) },
+            Primitive::Line { from: (0.0, 10.0), to: (10.0, 10.0) },
+            Primitive::Line { from: (10.0, 0.0), to: (20.0, 0.0) },
+            Primitive::Line { from: (20.0, 0.0), to: (20.0, 10.0) },
+            Primitive::Line { from: (10.0, 10.0), to: (20.0, 10.0) },
+        ]);
+
+        // Polar grids fill sectors, draw their inner walls as arcs and finish with the outer circle.
+        let grid = PolarGrid::new(3, 3);
+        let mut recorder = Recorder::new();
+        grid.draw(&mut recorder, &ConsoleGridFormatter, true, 10.0);
+        assert_eq!(recorder.size, Some((60.0, 60.0)));
+        assert!(recorder.fills().iter().all(|p| if let Primitive::Sector { .. } = p { true } else { false }));
+        assert_eq!(recorder.primitives.last(), Some(&Primitive::Arc { center: (30.0, 30.0), radius: 30.0, from: 0.0, to: 2.0 * std::f64::consts::PI }));
+    }
+
     // #[test]
     // fn kill_cells() {
     //     let grid = StandardGrid::new(5,5);
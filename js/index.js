@@ -11,19 +11,53 @@ const sizeSelector = document.querySelector("#size-selector");
 const algorithmSelector = document.querySelector("#algorithm-selector");
 const typeSelector = document.querySelector("#type-selector");
 const generateNew = document.querySelector("#new-maze");
+const seedInput = document.querySelector("#seed");
+
+// Names of the rust functions, by algorithm selector value. Used to rebuild a maze from its seed.
+const algorithmNames = {
+    1: "basic_binary_tree",
+    2: "sidewinder",
+    3: "aldous_broder",
+    4: "wilson",
+    5: "hunt_and_kill",
+    6: "recursive_backtracker",
+    7: "kruskal",
+    8: "simplified_prims",
+    9: "true_prims",
+    10: "growing_tree",
+    11: "recursive_division",
+    12: "houston",
+};
+
+let replayTimer;
+const stopReplay = () => clearInterval(replayTimer);
 
 const renderMaze = () => {
+    stopReplay();
+    // Some algorithms refuse some grid types, e.g. recursive division only works on the standard grid.
+    try {
+        buildMaze();
+    } catch (e) {
+        console.warn(e);
+    }
+};
+
+const buildMaze = () => {
     let alg = parseInt(algorithmSelector.value);
     let size = parseInt(sizeSelector.value);
-    
+
+    if (seedInput.value !== "") {
+        maze_generator.generate(parseInt(seedInput.value), algorithmNames[alg], size, size);
+        return;
+    }
 
     switch (alg) {
-        // case 1: 
-        //     maze_generator.basic_binary_tree(size, size);
-        //     break;
-        // case 2: 
-        //     maze_generator.sidewinder(size, size);
-        //     break;
+        case 1: 
+            maze_generator.basic_binary_tree(size, size, "ne");
+            break;
+        case 2: 
+            maze_generator.sidewinder(size, size, "ne");
+            break;
         case 3: 
             maze_generator.aldous_broder(size, size);
             break;
@@ -36,16 +70,57 @@ const renderMaze = () => {
         case 6: 
             maze_generator.recursive_backtracker(size, size);
             break;
+        case 7:
+            maze_generator.kruskal(size, size);
+            break;
+        case 8:
+            maze_generator.simplified_prims(size, size);
+            break;
+        case 9:
+            maze_generator.true_prims(size, size);
+            break;
+        case 10:
+            maze_generator.growing_tree(size, size);
+            break;
+        case 11:
+            maze_generator.recursive_division(size, size);
+            break;
+        case 12:
+            maze_generator.houston(size, size);
+            break;
         default:
             console.warn("not a valid value");
-            maze_generator.basic_binary_tree(size, size);
+            maze_generator.basic_binary_tree(size, size, "ne");
             break;
     }
 };
 
+// Builds the maze for the seed, or a random one, then carves it again on screen one passage at a time.
+const animateMaze = document.querySelector("#animate-maze");
+animateMaze.addEventListener("click", () => {
+    stopReplay();
+    let alg = parseInt(algorithmSelector.value);
+    let size = parseInt(sizeSelector.value);
+    let seed = seedInput.value !== "" ? parseInt(seedInput.value) : Math.floor(Math.random() * 0xffffffff);
+
+    try {
+        maze_generator.record_maze(seed, algorithmNames[alg], size, size);
+    } catch (e) {
+        console.warn(e);
+        return;
+    }
+
+    replayTimer = setInterval(() => {
+        if (!maze_generator.replay_step()) {
+            stopReplay();
+        }
+    }, 30);
+});
+
 sizeSelector.addEventListener("change", () => renderMaze());
 algorithmSelector.addEventListener("change", () => renderMaze());
 generateNew.addEventListener("click", () => renderMaze());
+seedInput.addEventListener("change", () => renderMaze());
 typeSelector.addEventListener("change", () => {
     
     let typeval = parseInt(typeSelector.value);
@@ -63,6 +138,9 @@ typeSelector.addEventListener("change", () => {
         case 4: 
             type = "triangle";
             break;
+        case 5:
+            type = "weave";
+            break;
         default:
             type = "polar";
             break;
@@ -71,6 +149,64 @@ typeSelector.addEventListener("change", () => {
     renderMaze();
 });
 
+const braid = document.querySelector("#braid");
+braid.addEventListener("change", () => {
+    maze_generator.on_braid_change(parseFloat(braid.value));
+    renderMaze();
+});
+
+// Grid type selector values, by the grid names used in saved mazes.
+const gridTypeValues = {
+    standard: 1,
+    masked: 1,
+    polar: 2,
+    hex: 3,
+    triangle: 4,
+    weave: 5,
+};
+
+const download = (contents, type, filename) => {
+    const link = document.createElement("a");
+    link.href = URL.createObjectURL(new Blob([contents], { type }));
+    link.download = filename;
+    link.click();
+    URL.revokeObjectURL(link.href);
+};
+
+const saveMaze = document.querySelector("#save-maze");
+saveMaze.addEventListener("click", () => {
+    download(maze_generator.save_maze(), "application/json", "maze.json");
+});
+
+const exportSvg = document.querySelector("#export-svg");
+exportSvg.addEventListener("click", () => {
+    download(maze_generator.export_svg(), "image/svg+xml", "maze.svg");
+});
+
+const exportPng = document.querySelector("#export-png");
+exportPng.addEventListener("click", () => {
+    download(maze_generator.export_png(20, 2), "image/png", "maze.png");
+});
+
+const loadMaze = document.querySelector("#load-maze");
+loadMaze.addEventListener("change", () => {
+    const file = loadMaze.files[0];
+    if (!file) {
+        return;
+    }
+
+    file.text().then(json => {
+        try {
+            stopReplay();
+            maze_generator.load_maze(json);
+            typeSelector.value = gridTypeValues[JSON.parse(json).grid];
+        } catch (e) {
+            console.warn(e);
+        }
+        loadMaze.value = "";
+    });
+});
+
 const colorize =  document.querySelector("#colorize");
 colorize.addEventListener("click", () => {
     maze_generator.on_colorize_change(colorize.checked);